@@ -276,12 +276,10 @@ impl MemTable {
 
     /// Spill this MemTable into a SpillWriter.
     /// If the MemTable is empty this is a no-op.
-    /// `chunk_target_size` is the target size of a serialized chunk of the spilled segment.
-    /// In practice, values like 256KB are reasonable.
+    /// Chunks of the spilled segment are sized per the SpillWriter's options.
     pub fn spill<F: io::Read + io::Write + io::Seek>(
         self,
         writer: &mut SpillWriter<F>,
-        chunk_target_size: usize,
     ) -> Result<Spec, Error> {
         let (sorted, mut spec, alloc) = self.try_into_parts()?;
 
@@ -315,7 +313,7 @@ impl MemTable {
             }
         }
 
-        let bytes = writer.write_segment(&sorted, chunk_target_size)?;
+        let bytes = writer.write_segment(&sorted)?;
         tracing::debug!(
             %bytes,
             entries=%sorted.len(),
@@ -436,7 +434,7 @@ mod test {
     use super::*;
     use serde_json::{json, Value};
 
-    use crate::{combine::CHUNK_TARGET_SIZE, SerPolicy, Validator};
+    use crate::{SerPolicy, Validator};
     use itertools::Itertools;
     use json::schema::build::build_schema;
 
@@ -998,7 +996,7 @@ mod test {
         add(&memtable, true, json!({"key": "ccc", "v": "bad"}));
 
         let mut spill = SpillWriter::new(io::Cursor::new(Vec::new())).unwrap();
-        let spec = memtable.spill(&mut spill, CHUNK_TARGET_SIZE).unwrap();
+        let spec = memtable.spill(&mut spill).unwrap();
 
         let (spill, ranges) = spill.into_parts();
         assert_eq!(ranges, vec![0..116]);
@@ -1019,7 +1017,7 @@ mod test {
         add(&memtable, false, json!({"key": "ddd", "v": "bad"}));
 
         let mut spill = SpillWriter::new(io::Cursor::new(Vec::new())).unwrap();
-        let out = memtable.spill(&mut spill, CHUNK_TARGET_SIZE);
+        let out = memtable.spill(&mut spill);
         assert!(matches!(out, Err(Error::FailedValidation(_))));
     }
 
@@ -14,6 +14,8 @@ pub enum Error {
     FailedValidation(#[source] FailedValidation),
     #[error(transparent)]
     SchemaError(#[from] json::schema::index::Error),
+    #[error("failed to build collection JSON Schema")]
+    SchemaBuild(#[from] json::schema::build::Error),
     #[error("spill file IO error")]
     SpillIO(#[from] io::Error),
 }
@@ -74,6 +76,40 @@ impl Spec {
     }
 }
 
+/// SpecBuilder builds a Spec from bindings having JSON schemas,
+/// which are compiled into validators upon `build()`.
+#[derive(Default)]
+pub struct SpecBuilder {
+    bindings: Vec<(bool, Vec<Extractor>, serde_json::Value)>,
+}
+
+impl SpecBuilder {
+    /// Add a binding with the given full-reduction mode, key, and JSON schema.
+    /// See `Spec::with_one_binding` for the meaning of `full`.
+    pub fn add_binding(
+        mut self,
+        full: bool,
+        key: Vec<Extractor>,
+        schema: serde_json::Value,
+    ) -> Self {
+        self.bindings.push((full, key, schema));
+        self
+    }
+
+    /// Build a Spec of the added bindings, compiling each of their schemas.
+    pub fn build(self) -> Result<Spec, Error> {
+        let mut bindings = Vec::with_capacity(self.bindings.len());
+
+        for (index, (full, key, schema)) in self.bindings.into_iter().enumerate() {
+            let curi = url::Url::parse(&format!("spec-builder://binding/{index}")).unwrap();
+            let schema = crate::validation::build_schema(curi.clone(), &schema)?;
+
+            bindings.push((full, key, Some(curi), Validator::new(schema)?));
+        }
+        Ok(Spec::with_bindings(bindings))
+    }
+}
+
 /// Meta is metadata about an entry: its binding index and flags.
 #[derive(Eq, PartialEq, Clone, Copy)]
 pub struct Meta(u32);
@@ -89,7 +125,7 @@ pub mod memtable;
 pub use memtable::{MemDrainer, MemTable};
 
 pub mod spill;
-pub use spill::{SpillDrainer, SpillWriter};
+pub use spill::{DrainStats, SpillDrainer, SpillWriter, SpillWriterOptions};
 
 /// Accumulator is a MemTable paired with a File-backed SpillWriter.
 /// As the caller utilizes the MemTable the Accumulator will transparently
@@ -102,9 +138,19 @@ pub struct Accumulator {
 
 impl Accumulator {
     pub fn new(spec: Spec, spill: std::fs::File) -> Result<Self, Error> {
+        Self::with_options(spec, spill, SpillWriterOptions::default())
+    }
+
+    /// Build an Accumulator which spills using the given SpillWriterOptions.
+    /// The options are retained across subsequent Drainer::into_new_accumulator().
+    pub fn with_options(
+        spec: Spec,
+        spill: std::fs::File,
+        options: SpillWriterOptions,
+    ) -> Result<Self, Error> {
         Ok(Self {
             memtable: Some(MemTable::new(spec)),
-            spill: SpillWriter::new(spill)?,
+            spill: SpillWriter::with_options(spill, options)?,
         })
     }
 
@@ -121,11 +167,7 @@ impl Accumulator {
         };
 
         if bump_mem_used(memtable.alloc()) > BUMP_THRESHOLD {
-            let spec = self
-                .memtable
-                .take()
-                .unwrap()
-                .spill(spill, CHUNK_TARGET_SIZE)?;
+            let spec = self.memtable.take().unwrap().spill(spill)?;
             self.memtable = Some(MemTable::new(spec));
         }
 
@@ -143,33 +185,40 @@ impl Accumulator {
             unreachable!("memtable must be Some");
         };
 
+        let options = spill.options().clone();
+
         if spill.segment_ranges().is_empty() {
             let (spill, _ranges) = spill.into_parts();
 
             Ok(Drainer::Mem {
                 spill,
                 drainer: memtable.try_into_drainer()?,
+                options,
             })
         } else {
             // Spill the final MemTable segment.
-            let spec = memtable.spill(&mut spill, CHUNK_TARGET_SIZE)?;
+            let spec = memtable.spill(&mut spill)?;
             let (spill, ranges) = spill.into_parts();
 
             Ok(Drainer::Spill {
                 drainer: SpillDrainer::new(spec, spill, &ranges)?,
+                options,
             })
         }
     }
 }
 
 /// Drainer drains from either a wrapped MemTable or SpillDrainer.
+/// Each retains the SpillWriterOptions of its Accumulator.
 pub enum Drainer {
     Mem {
         spill: std::fs::File,
         drainer: MemDrainer,
+        options: SpillWriterOptions,
     },
     Spill {
         drainer: SpillDrainer<std::fs::File>,
+        options: SpillWriterOptions,
     },
 }
 
@@ -185,7 +234,7 @@ impl Iterator for Drainer {
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             Self::Mem { drainer, .. } => drainer.next(),
-            Self::Spill { drainer } => drainer.next(),
+            Self::Spill { drainer, .. } => drainer.next(),
         }
     }
 }
@@ -195,7 +244,7 @@ impl Drainer {
     pub fn drain_next(&mut self) -> Result<Option<DrainedDoc>, Error> {
         match self {
             Self::Mem { drainer, .. } => drainer.drain_next(),
-            Self::Spill { drainer } => drainer.drain_next(),
+            Self::Spill { drainer, .. } => drainer.drain_next(),
         }
     }
 
@@ -203,17 +252,21 @@ impl Drainer {
     /// Any un-drained documents are dropped.
     pub fn into_new_accumulator(self) -> Result<Accumulator, Error> {
         match self {
-            Drainer::Mem { spill, drainer } => {
+            Drainer::Mem {
+                spill,
+                drainer,
+                options,
+            } => {
                 let spec = drainer.into_spec();
-                Ok(Accumulator::new(spec, spill)?)
+                Ok(Accumulator::with_options(spec, spill, options)?)
             }
-            Drainer::Spill { drainer } => {
+            Drainer::Spill { drainer, options } => {
                 let (spec, mut spill) = drainer.into_parts();
 
                 spill.seek(io::SeekFrom::Start(0))?; // Reset to start.
                 spill.set_len(0)?; // Release allocated size to OS.
 
-                Ok(Accumulator::new(spec, spill)?)
+                Ok(Accumulator::with_options(spec, spill, options)?)
             }
         }
     }
@@ -347,7 +400,79 @@ const BUMP_THRESHOLD: usize = 1 << 28; // 256MB.
 //  - Larger, so that filesystem reads and writes are amortized for the
 //    LZ4-compressed.
 //
+// By default, SpillWriters use auto_chunk_target_size(), which scales the
+// chunk target with the memory limit of the process within
+// CHUNK_TARGET_SIZE_{MIN,MAX}. CHUNK_TARGET_SIZE is used if the limit is unknown.
+//
+// The bounds are tighter than memory alone would suggest, because SpillDrainer
+// holds an uncompressed chunk of _every_ segment in memory, and a segment is at
+// most BUMP_THRESHOLD in size: chunks beyond 16MB buy little additional
+// compression or IO amortization, while multiplying drain memory by the
+// number of segments.
 const CHUNK_TARGET_SIZE: usize = 1 << 18; // 256KB.
+const CHUNK_TARGET_SIZE_MIN: usize = CHUNK_TARGET_SIZE;
+const CHUNK_TARGET_SIZE_MAX: usize = 1 << 24; // 16MB.
+
+/// Select a spill chunk target size appropriate for the memory limit of this
+/// process. Processes with ample memory use larger chunks, which means fewer
+/// chunks to read and decompress while draining. Processes with little memory
+/// use smaller chunks, reducing the risk of OOM as SpillDrainer holds one
+/// uncompressed chunk of every spilled segment in memory.
+///
+/// The memory limit is that of the process's cgroup, or else the total memory
+/// of the host. The target is 1/1024th of the limit, clamped to [256KB, 16MB],
+/// and is CHUNK_TARGET_SIZE if the limit cannot be determined. It's computed
+/// once and then cached.
+pub fn auto_chunk_target_size() -> usize {
+    *AUTO_CHUNK_TARGET_SIZE
+}
+
+lazy_static::lazy_static! {
+    static ref AUTO_CHUNK_TARGET_SIZE: usize = {
+        let read = |path| std::fs::read_to_string(path).ok();
+        let limit = memory_limit(
+            read("/sys/fs/cgroup/memory.max").as_deref(),
+            read("/sys/fs/cgroup/memory/memory.limit_in_bytes").as_deref(),
+            read("/proc/meminfo").as_deref(),
+        );
+        chunk_target_size_for(limit)
+    };
+}
+
+fn chunk_target_size_for(memory_limit: Option<usize>) -> usize {
+    match memory_limit {
+        Some(limit) => (limit / 1024).clamp(CHUNK_TARGET_SIZE_MIN, CHUNK_TARGET_SIZE_MAX),
+        None => CHUNK_TARGET_SIZE,
+    }
+}
+
+// Memory limit in bytes, given the contents of the cgroup v2 `memory.max`,
+// the cgroup v1 `memory.limit_in_bytes`, and /proc/meminfo. The limit is the
+// least of a cgroup limit (if any) and the host's `MemTotal`.
+fn memory_limit(
+    cgroup_v2: Option<&str>,
+    cgroup_v1: Option<&str>,
+    meminfo: Option<&str>,
+) -> Option<usize> {
+    // An unlimited cgroup v2 is "max", and an unlimited cgroup v1 is a very
+    // large number which is then bounded by MemTotal.
+    let cgroup = cgroup_v2
+        .or(cgroup_v1)
+        .and_then(|limit| limit.trim().parse::<usize>().ok());
+
+    let total = meminfo.and_then(|meminfo| {
+        meminfo.lines().find_map(|line| {
+            let kb = line.strip_prefix("MemTotal:")?.trim();
+            let kb = kb.strip_suffix("kB")?.trim();
+            kb.parse::<usize>().ok().map(|kb| kb * 1024)
+        })
+    });
+
+    match (cgroup, total) {
+        (Some(cgroup), Some(total)) => Some(cgroup.min(total)),
+        (cgroup, total) => cgroup.or(total),
+    }
+}
 
 // These are compile-time assertions that document and enforce that Combiner
 // and friends implement Send.
@@ -367,3 +492,113 @@ fn _assert_combiner_is_send(t: Combiner) {
     _assert_send(t)
 }
 fn _assert_send<T: Send>(_t: T) {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_auto_chunk_target_size() {
+        let size = auto_chunk_target_size();
+        assert!(
+            (CHUNK_TARGET_SIZE_MIN..=CHUNK_TARGET_SIZE_MAX).contains(&size),
+            "{size} is outside of the expected range"
+        );
+        // The size is computed only once.
+        assert_eq!(size, auto_chunk_target_size());
+        // It's the default of SpillWriters.
+        assert_eq!(SpillWriterOptions::default().chunk_target_size, size);
+
+        // The fallback is within the clamped range.
+        assert_eq!(chunk_target_size_for(None), CHUNK_TARGET_SIZE);
+        assert_eq!(chunk_target_size_for(Some(1 << 20)), CHUNK_TARGET_SIZE_MIN);
+        assert_eq!(chunk_target_size_for(Some(1 << 30)), 1 << 20);
+        assert_eq!(chunk_target_size_for(Some(1 << 40)), CHUNK_TARGET_SIZE_MAX);
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let meminfo =
+            "MemTotal:       16384 kB\nMemFree:        1024 kB\nMemAvailable:   2048 kB\n";
+
+        // cgroup v2 limits, or their absence.
+        assert_eq!(
+            memory_limit(Some("8192\n"), None, Some(meminfo)),
+            Some(8192)
+        );
+        assert_eq!(
+            memory_limit(Some("max\n"), None, Some(meminfo)),
+            Some(16384 * 1024)
+        );
+        // cgroup v1 limits, which are bounded by the host's memory.
+        assert_eq!(
+            memory_limit(None, Some("4096\n"), Some(meminfo)),
+            Some(4096)
+        );
+        assert_eq!(
+            memory_limit(None, Some("9223372036854771712\n"), Some(meminfo)),
+            Some(16384 * 1024)
+        );
+        // No cgroup, or no host memory.
+        assert_eq!(memory_limit(None, None, Some(meminfo)), Some(16384 * 1024));
+        assert_eq!(memory_limit(Some("8192"), None, None), Some(8192));
+        assert_eq!(memory_limit(None, None, Some("garbage")), None);
+        assert_eq!(memory_limit(None, None, None), None);
+    }
+
+    #[test]
+    fn test_spec_builder() {
+        let spec = SpecBuilder::default().build().unwrap();
+        assert!(spec.is_full.is_empty());
+
+        let key = vec![Extractor::new("/key", &crate::SerPolicy::noop())];
+        let spec = SpecBuilder::default()
+            .add_binding(true, key.clone(), serde_json::json!({"type": "object"}))
+            .add_binding(false, key.clone(), serde_json::json!(true))
+            .build()
+            .unwrap();
+        assert_eq!(spec.is_full, vec![true, false]);
+        assert_eq!(spec.keys.len(), 2);
+        assert_eq!(spec.validators.len(), 2);
+        assert_eq!(
+            spec.validators[1].1.as_ref().map(url::Url::as_str),
+            Some("spec-builder://binding/1")
+        );
+
+        // Invalid schemas fail to build.
+        assert!(matches!(
+            SpecBuilder::default()
+                .add_binding(true, key, serde_json::json!({"type": 42}))
+                .build(),
+            Err(Error::SchemaBuild(_))
+        ));
+    }
+
+    #[test]
+    fn test_accumulator_options() {
+        let key = vec![Extractor::new("/key", &crate::SerPolicy::noop())];
+        let spec = SpecBuilder::default()
+            .add_binding(true, key, serde_json::json!(true))
+            .build()
+            .unwrap();
+        let options = SpillWriterOptions {
+            chunk_target_size: 4096,
+            adaptive: true,
+        };
+
+        let mut accumulator =
+            Accumulator::with_options(spec, tempfile::tempfile().unwrap(), options).unwrap();
+        let memtable = accumulator.memtable().unwrap();
+        let doc = HeapNode::from_node(&serde_json::json!({"key": "a"}), memtable.alloc());
+        memtable.add(0, doc, false).unwrap();
+
+        // Options are retained across a drain and into the next Accumulator.
+        let mut drainer = accumulator.into_drainer().unwrap();
+        assert!(drainer.drain_next().unwrap().is_some());
+        let accumulator = drainer.into_new_accumulator().unwrap();
+
+        let options = accumulator.spill.options();
+        assert_eq!(options.chunk_target_size, 4096);
+        assert!(options.adaptive);
+    }
+}
@@ -7,18 +7,53 @@ use rkyv::ser::Serializer;
 use std::collections::BinaryHeap;
 use std::ops::Range;
 use std::sync::Arc;
+use std::time::Instant;
 use std::{cmp, io};
 
+/// SpillWriterOptions are options of a SpillWriter.
+#[derive(Debug, Clone)]
+pub struct SpillWriterOptions {
+    /// Target size of the uncompressed chunks of spilled segments.
+    /// Defaults to auto_chunk_target_size(), which scales with available memory.
+    pub chunk_target_size: usize,
+    /// When true, chunks are sized by their estimated compressed size rather
+    /// than their uncompressed size. The estimate uses the compression ratio
+    /// of the segment's previous chunk. Highly compressible documents then
+    /// produce fewer and larger chunks, which compress better. Uncompressed
+    /// chunks are bounded to MAX_ADAPTIVE_EXPANSION times the chunk target.
+    pub adaptive: bool,
+}
+
+impl Default for SpillWriterOptions {
+    fn default() -> Self {
+        Self {
+            chunk_target_size: super::auto_chunk_target_size(),
+            adaptive: false,
+        }
+    }
+}
+
+// Upper bound of the uncompressed size of adaptive chunks, as a multiple of
+// the chunk target. SpillDrainer holds one uncompressed chunk of every
+// segment in memory, so this bounds its memory usage.
+const MAX_ADAPTIVE_EXPANSION: usize = 8;
+
 /// SpillWriter writes segments of sorted documents to a spill file,
 /// and tracks each of the written segment range offsets within the file.
 pub struct SpillWriter<F: io::Read + io::Write + io::Seek> {
+    options: SpillWriterOptions,
     ranges: Vec<Range<u64>>,
     spill: F,
 }
 
 impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
-    /// Build a SpillWriter around the given spill file.
-    pub fn new(mut spill: F) -> Result<Self, std::io::Error> {
+    /// Build a SpillWriter around the given spill file, using default options.
+    pub fn new(spill: F) -> Result<Self, std::io::Error> {
+        Self::with_options(spill, SpillWriterOptions::default())
+    }
+
+    /// Build a SpillWriter around the given spill file and options.
+    pub fn with_options(mut spill: F, options: SpillWriterOptions) -> Result<Self, std::io::Error> {
         assert_eq!(
             spill.seek(io::SeekFrom::Current(0))?,
             0,
@@ -26,28 +61,35 @@ impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
         );
 
         Ok(Self {
+            options,
             ranges: Vec::new(),
             spill,
         })
     }
 
+    /// Options of this SpillWriter.
+    pub fn options(&self) -> &SpillWriterOptions {
+        &self.options
+    }
+
     /// Write a segment to the spill file. The segment array documents must
     /// already be in sorted key order. Documents will be grouped into chunks
-    /// of the given size, and are then written in-order to the spill file.
-    /// Each chunks is compressed using LZ4.
+    /// of the options' `chunk_target_size`, and are then written in-order to
+    /// the spill file. Each chunks is compressed using LZ4.
     /// The written size of the segment is returned.
-    pub fn write_segment(
-        &mut self,
-        entries: &[HeapEntry<'_>],
-        chunk_target_size: usize,
-    ) -> Result<u64, io::Error> {
+    pub fn write_segment(&mut self, entries: &[HeapEntry<'_>]) -> Result<u64, io::Error> {
         if entries.is_empty() {
             return Ok(0);
         }
+        let chunk_target_size = self.options.chunk_target_size;
 
         let begin = self.spill.seek(io::SeekFrom::Current(0))?;
 
-        let mut last_chunk_index = 0;
+        // Index of the first document of the current chunk.
+        let mut chunk_begin = 0;
+        let mut chunks = 0;
+        // Ratio of compressed to uncompressed bytes of the last written chunk.
+        let mut compression_ratio = 1.0;
         let mut lz4_buf = Vec::new();
         let mut raw_buf = rkyv::AlignedVec::with_capacity(2 * chunk_target_size);
         let mut rkyv_scratch = Default::default();
@@ -81,7 +123,13 @@ impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
             raw_buf[offset + 4..offset + 8].copy_from_slice(&u32::to_le_bytes(doc_len as u32));
 
             // If this isn't the last element and our chunk is under threshold then continue accruing documents.
-            if index != entries.len() - 1 && raw_buf.len() < chunk_target_size {
+            let chunk_size = if self.options.adaptive {
+                let estimate = (raw_buf.len() as f64 * compression_ratio) as usize;
+                estimate.max(raw_buf.len() / MAX_ADAPTIVE_EXPANSION)
+            } else {
+                raw_buf.len()
+            };
+            if index != entries.len() - 1 && chunk_size < chunk_target_size {
                 continue;
             }
             // We have a complete chunk. Next we compress and write it to the spill file.
@@ -99,6 +147,7 @@ impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
             )?;
             // Safety: lz4 will not write beyond our given slice.
             unsafe { lz4_buf.set_len(8 + n) };
+            compression_ratio = n as f64 / raw_buf.len() as f64;
 
             // Update the header with the raw and lz4'd chunk lengths, then send to writer.
             let lz4_len = u32::to_ne_bytes(lz4_buf.len() as u32 - 8);
@@ -108,16 +157,18 @@ impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
 
             self.spill.write_all(&lz4_buf)?;
 
+            let chunk_docs = 1 + index - chunk_begin;
             tracing::trace!(
-                chunk_docs = %(1 + index - last_chunk_index),
-                bytes_per_doc = (raw_buf.len() / (1 + index - last_chunk_index)),
+                %chunk_docs,
+                bytes_per_doc = (raw_buf.len() / chunk_docs),
                 raw_len = %raw_buf.len(),
                 lz4_len = %lz4_buf.len(),
                 remaining = %(entries.len() - (1 + index)),
                 "wrote chunk",
             );
 
-            last_chunk_index = index;
+            chunk_begin = index + 1;
+            chunks += 1;
             lz4_buf.clear();
             raw_buf.clear();
         }
@@ -125,6 +176,13 @@ impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
         let end = self.spill.seek(io::SeekFrom::Current(0))?;
         self.ranges.push(begin..end);
 
+        tracing::trace!(
+            docs = entries.len(),
+            %chunks,
+            range = ?(begin..end),
+            "wrote segment",
+        );
+
         Ok(end - begin)
     }
 
@@ -134,7 +192,11 @@ impl<F: io::Read + io::Write + io::Seek> SpillWriter<F> {
 
     /// Destructure the SpillWriter into its spill file and segment ranges.
     pub fn into_parts(self) -> (F, Vec<Range<u64>>) {
-        let Self { spill, ranges } = self;
+        let Self {
+            options: _,
+            spill,
+            ranges,
+        } = self;
         (spill, ranges)
     }
 }
@@ -184,10 +246,11 @@ impl Entry {
 /// Entries are written to the spill file in sorted order within a segment,
 /// so this iterator-like object will yield entries in ascending order.
 struct Segment {
-    head: Entry,                   // Next Entry of Segment.
+    docs: usize, // Entries remaining in the current chunk, including `head`.
+    head: Entry, // Next Entry of Segment.
     keys: Arc<[Box<[Extractor]>]>, // Keys for comparing Entries across Segments.
-    next: Range<u64>,              // Next chunk of this Segment.
-    tail: bytes::Bytes,            // Remainder of the current chunk.
+    next: Range<u64>, // Next chunk of this Segment.
+    tail: bytes::Bytes, // Remainder of the current chunk.
 }
 
 impl Segment {
@@ -238,9 +301,11 @@ impl Segment {
         }
 
         let chunk: bytes::Bytes = raw_buf.into_vec().into();
+        let docs = count_entries(&chunk);
         let (head, tail) = Entry::parse(chunk)?;
 
         Ok(Self {
+            docs,
             head,
             keys,
             next,
@@ -255,6 +320,7 @@ impl Segment {
         r: &mut R,
     ) -> Result<(Entry, Option<Self>), io::Error> {
         let Segment {
+            docs,
             head: popped,
             keys,
             next,
@@ -267,6 +333,7 @@ impl Segment {
             Ok((
                 popped,
                 Some(Self {
+                    docs: docs - 1,
                     head,
                     keys,
                     next,
@@ -279,6 +346,63 @@ impl Segment {
             Ok((popped, None))
         }
     }
+
+    /// Number of Entries remaining in the current chunk, including the head.
+    fn doc_count(&self) -> usize {
+        self.docs
+    }
+
+    /// Remaining bytes of the Segment: the raw bytes which remain in the current
+    /// chunk, plus the compressed bytes of chunks which have yet to be read.
+    fn total_range_bytes(&self) -> u64 {
+        self.next.end - self.next.start + self.tail.len() as u64
+    }
+
+    /// Key extractors of each binding, which order Segments.
+    #[allow(dead_code)]
+    fn keys(&self) -> &[Box<[Extractor]>] {
+        &self.keys
+    }
+
+    /// Peek at the Entry which follows the head within the current chunk,
+    /// without advancing the Segment. Returns None if the head is the last
+    /// Entry of its chunk, even if further chunks remain to be read.
+    #[allow(dead_code)]
+    fn peek_next_key(&self) -> Option<PeekedKey<'_>> {
+        if self.tail.len() < 8 {
+            return None;
+        }
+        let meta = Meta::from_bytes(self.tail[0..4].try_into().unwrap());
+        let doc_len = u32::from_le_bytes(self.tail[4..8].try_into().unwrap()) as usize;
+        let doc = self.tail.get(8..8 + doc_len)?;
+
+        // Safety: the document was archived by SpillWriter, as was the head.
+        let root = unsafe { rkyv::archived_root::<HeapNode>(doc) };
+        Some(PeekedKey { meta, root })
+    }
+}
+
+// Count the Entries of a SpillWriter chunk by walking their headers.
+// A truncated trailing Entry isn't counted, and fails upon its parse.
+fn count_entries(mut chunk: &[u8]) -> usize {
+    let mut count = 0;
+
+    while chunk.len() >= 8 {
+        let doc_len = u32::from_le_bytes(chunk[4..8].try_into().unwrap()) as usize;
+        let Some(rest) = chunk.get(8 + doc_len..) else {
+            break;
+        };
+        chunk = rest;
+        count += 1;
+    }
+    count
+}
+
+/// PeekedKey is a borrowed, un-parsed view of the next Entry of a Segment chunk.
+#[allow(dead_code)]
+struct PeekedKey<'s> {
+    meta: Meta,
+    root: &'s crate::ArchivedNode,
 }
 
 impl Ord for Segment {
@@ -316,25 +440,60 @@ pub struct SpillDrainer<F: io::Read + io::Seek> {
     alloc: Arc<Bump>, // Used for individual key reductions.
     heap: BinaryHeap<cmp::Reverse<Segment>>,
     in_group: bool,
+    // Segment ranges of the spill file, from which the heap is (re)built.
+    original_ranges: Vec<Range<u64>>,
     spec: Spec,
     spill: F,
+    stats: DrainStats,
+    // Binding most recently drained of a full-reduction binding,
+    // and the packed keys of its drained documents.
+    #[cfg(debug_assertions)]
+    full_keys: Option<(usize, std::collections::HashSet<bytes::Bytes>)>,
 }
 
 // Safety: SpillDrainer is safe to Send because it wraps Bump with Arc,
 // and we emit OwnedNodes that own a reference count to the Bump.
 unsafe impl<F: io::Read + io::Seek> Send for SpillDrainer<F> {}
 
+/// DrainStats are statistics of a SpillDrainer, which are updated as documents are drained.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DrainStats {
+    /// Number of documents drained.
+    pub total_docs: u64,
+    /// Number of bytes read from the spill file, including chunk headers.
+    pub total_bytes_read: u64,
+    /// Number of reductions of a spilled document into another.
+    pub total_reductions: u64,
+    /// Number of chunks read from the spill file.
+    pub chunk_reads: u64,
+    /// Total nanoseconds spent draining documents.
+    pub elapsed_ns: u64,
+}
+
 impl<F: io::Read + io::Seek> SpillDrainer<F> {
     pub fn drain_next(&mut self) -> Result<Option<DrainedDoc>, Error> {
+        let started = Instant::now();
+        let drained = self.drain_entry();
+
+        self.stats.elapsed_ns += started.elapsed().as_nanos() as u64;
+        if let Ok(Some(_)) = &drained {
+            self.stats.total_docs += 1;
+        }
+        drained
+    }
+
+    /// Statistics of documents drained thus far.
+    pub fn drain_stats(&self) -> &DrainStats {
+        &self.stats
+    }
+
+    fn drain_entry(&mut self) -> Result<Option<DrainedDoc>, Error> {
         let Some(cmp::Reverse(segment)) = self.heap.pop() else {
             return Ok(None);
         };
 
         // Pop `segment`'s next Entry, and then re-heap it.
-        let (entry, segment) = segment.pop_head(&mut self.spill)?;
-        if let Some(segment) = segment {
-            self.heap.push(cmp::Reverse(segment));
-        }
+        let entry = pop_head_and_reheap(&mut self.heap, &mut self.spill, &mut self.stats, segment)?;
 
         let Entry { mut meta, root } = entry;
         let is_full = self.spec.is_full[meta.binding()];
@@ -378,13 +537,16 @@ impl<F: io::Read + io::Seek> SpillDrainer<F> {
                 Ok((node, deleted)) => {
                     meta.set_deleted(deleted);
                     reduced = Some(node);
+                    self.stats.total_reductions += 1;
 
                     // Discard the peeked entry, which was reduced into `reduced_root`.
                     let segment = self.heap.pop().unwrap().0;
-                    let (_discard, segment) = segment.pop_head(&mut self.spill)?;
-                    if let Some(segment) = segment {
-                        self.heap.push(cmp::Reverse(segment));
-                    }
+                    let _discard = pop_head_and_reheap(
+                        &mut self.heap,
+                        &mut self.spill,
+                        &mut self.stats,
+                        segment,
+                    )?;
                 }
                 Err(reduce::Error::NotAssociative) => {
                     meta.set_not_associative();
@@ -430,8 +592,63 @@ impl<F: io::Read + io::Seek> SpillDrainer<F> {
             }
         };
 
+        #[cfg(debug_assertions)]
+        if is_full {
+            self.check_full_drain_once(meta.binding(), &root);
+        }
+
         Ok(Some(DrainedDoc { meta, root }))
     }
+
+    // Check that each (binding, key) of full-reduction bindings is drained once.
+    // A drained document has been fully reduced with all other documents of its
+    // key, and it's a bug in the drain logic if we later drain another document
+    // having the same (binding, key). Bindings are drained in ascending order,
+    // so only the packed keys of the current binding are retained.
+    //
+    // Packed keys are compared only for equality, because the order of their
+    // tuple encoding differs from that of Extractor::compare_key
+    // (for example, when comparing integers with floats).
+    #[cfg(debug_assertions)]
+    fn check_full_drain_once(&mut self, binding: usize, root: &OwnedNode) {
+        let key =
+            Extractor::extract_all_owned(root, &self.spec.keys[binding], &mut Default::default());
+
+        match &mut self.full_keys {
+            Some((last_binding, keys)) if *last_binding == binding => {
+                assert!(
+                    keys.insert(key),
+                    "fully-reduced document of binding {binding} was drained more than once: {}",
+                    serde_json::to_value(crate::SerPolicy::noop().on_owned(root)).unwrap(),
+                );
+            }
+            Some((last_binding, _)) if *last_binding > binding => {
+                panic!("fully-reduced document of binding {binding} was drained after binding {last_binding}");
+            }
+            _ => self.full_keys = Some((binding, [key].into())),
+        }
+    }
+}
+
+// Pop the head Entry of `segment` and re-heap its remainder, if any.
+// If a next chunk of the segment was read, it's accounted for in `stats`.
+fn pop_head_and_reheap<R: io::Read + io::Seek>(
+    heap: &mut BinaryHeap<cmp::Reverse<Segment>>,
+    spill: &mut R,
+    stats: &mut DrainStats,
+    segment: Segment,
+) -> Result<Entry, io::Error> {
+    let offset = segment.next.start;
+    let (entry, segment) = segment.pop_head(spill)?;
+
+    if let Some(segment) = segment {
+        if segment.next.start != offset {
+            stats.chunk_reads += 1;
+            stats.total_bytes_read += segment.next.start - offset;
+        }
+        heap.push(cmp::Reverse(segment));
+    }
+    Ok(entry)
 }
 
 impl<F: io::Read + io::Seek> Iterator for SpillDrainer<F> {
@@ -446,39 +663,88 @@ impl<F: io::Read + io::Seek> SpillDrainer<F> {
     /// Build a new SpillDrainer which drains the given segment ranges previously
     /// written to the spill file.
     pub fn new(spec: Spec, mut spill: F, ranges: &[Range<u64>]) -> Result<Self, std::io::Error> {
-        let mut heap = BinaryHeap::with_capacity(ranges.len());
-
-        for range in ranges {
-            let segment = Segment::new(spec.keys.clone(), &mut spill, range.clone())?;
-            heap.push(cmp::Reverse(segment));
-        }
+        let mut stats = DrainStats::default();
+        let heap = build_heap(&spec, &mut spill, ranges, &mut stats)?;
 
         Ok(Self {
             alloc: Arc::new(Bump::new()),
             heap,
             in_group: false,
+            original_ranges: ranges.to_vec(),
             spec,
             spill,
+            stats,
+            #[cfg(debug_assertions)]
+            full_keys: None,
         })
     }
 
+    /// Rewind the SpillDrainer to the beginning of its segment ranges,
+    /// so that its documents may be drained again in a subsequent pass.
+    /// DrainStats continue to accumulate across passes.
+    pub fn rewind(&mut self) -> Result<(), std::io::Error> {
+        self.heap = build_heap(
+            &self.spec,
+            &mut self.spill,
+            &self.original_ranges,
+            &mut self.stats,
+        )?;
+        self.in_group = false;
+
+        #[cfg(debug_assertions)]
+        {
+            self.full_keys = None;
+        }
+        Ok(())
+    }
+
     pub fn into_parts(self) -> (Spec, F) {
         let Self {
             alloc: _,
             heap: _,
             in_group: _,
+            original_ranges: _,
             spec,
             spill,
+            stats: _,
+            #[cfg(debug_assertions)]
+                full_keys: _,
         } = self;
         (spec, spill)
     }
 }
 
+// Build a heap of Segments over each of the `ranges` of the spill file.
+fn build_heap<F: io::Read + io::Seek>(
+    spec: &Spec,
+    spill: &mut F,
+    ranges: &[Range<u64>],
+    stats: &mut DrainStats,
+) -> Result<BinaryHeap<cmp::Reverse<Segment>>, std::io::Error> {
+    let mut heap = BinaryHeap::with_capacity(ranges.len());
+
+    for range in ranges {
+        let segment = Segment::new(spec.keys.clone(), spill, range.clone())?;
+        stats.chunk_reads += 1;
+        stats.total_bytes_read += segment.next.start - range.start;
+
+        tracing::trace!(
+            ?range,
+            chunk_docs = segment.doc_count(),
+            remaining_bytes = segment.total_range_bytes(),
+            "read initial segment chunk",
+        );
+        heap.push(cmp::Reverse(segment));
+    }
+    Ok(heap)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{
-        combine::CHUNK_TARGET_SIZE, validation::build_schema, HeapNode, SerPolicy, Validator,
+        combine::{SpecBuilder, CHUNK_TARGET_SIZE},
+        HeapNode, SerPolicy,
     };
     use itertools::Itertools;
     use serde_json::{json, Value};
@@ -496,10 +762,9 @@ mod test {
         let keys: Arc<[Box<[Extractor]>]> = Vec::new().into();
 
         // Write segment fixture into a SpillWriter.
-        let mut spill = SpillWriter::new(io::Cursor::new(Vec::new())).unwrap();
-
         // 130 is calibrated to include two, but not three documents in a chunk.
-        spill.write_segment(&segment, 130).unwrap();
+        let mut spill = spill_writer(130);
+        spill.write_segment(&segment).unwrap();
         let (mut spill, ranges) = spill.into_parts();
 
         // Assert we wrote the expected range and regression fixture.
@@ -530,6 +795,16 @@ mod test {
         assert!(crate::compare(segment.head.root.get(), &fixture[0].1).is_eq());
         assert!(!segment.tail.is_empty());
         assert_eq!(segment.next, 112..186);
+        assert_eq!(segment.doc_count(), 2);
+        assert_eq!(segment.total_range_bytes(), 74 + segment.tail.len() as u64);
+        assert!(segment.keys().is_empty());
+
+        // Peek the second document of the chunk, which doesn't advance the Segment.
+        let peeked = segment.peek_next_key().unwrap();
+        assert_eq!(peeked.meta.binding(), 1);
+        assert!(crate::compare(peeked.root, &fixture[1].1).is_eq());
+        assert!(crate::compare(segment.head.root.get(), &fixture[0].1).is_eq());
+        assert_eq!(segment.doc_count(), 2);
 
         let (_, next_segment) = segment.pop_head(&mut spill).unwrap();
         segment = next_segment.unwrap();
@@ -539,6 +814,9 @@ mod test {
         assert!(crate::compare(segment.head.root.get(), &fixture[1].1).is_eq());
         assert!(segment.tail.is_empty()); // Chunk is empty.
         assert_eq!(segment.next, 112..186);
+        assert_eq!(segment.doc_count(), 1);
+        assert_eq!(segment.total_range_bytes(), 74);
+        assert!(segment.peek_next_key().is_none()); // Head is last of its chunk.
 
         // Next chunk is read and has one document.
         let (_, next_segment) = segment.pop_head(&mut spill).unwrap();
@@ -549,6 +827,9 @@ mod test {
         assert!(crate::compare(segment.head.root.get(), &fixture[2].1).is_eq());
         assert!(segment.tail.is_empty()); // Chunk is empty.
         assert_eq!(segment.next, 186..186);
+        assert_eq!(segment.doc_count(), 1);
+        assert_eq!(segment.total_range_bytes(), 0);
+        assert!(segment.peek_next_key().is_none()); // Single-document chunk.
 
         // Stepping the segment again consumes it, as no chunks remain.
         let (_, next_segment) = segment.pop_head(&mut spill).unwrap();
@@ -556,86 +837,56 @@ mod test {
     }
 
     #[test]
-    fn test_heap_merge() {
-        let spec = Spec::with_bindings(
-            std::iter::repeat_with(|| {
-                let schema = build_schema(
-                    url::Url::parse("http://example/schema").unwrap(),
-                    &json!({
-                        "properties": {
-                            "key": { "type": "string", "default": "def" },
-                            "v": {
-                                "type": "array",
-                                "reduce": { "strategy": "append" }
-                            }
-                        },
-                        "reduce": { "strategy": "merge" }
-                    }),
-                )
-                .unwrap();
-
-                (
-                    true, // Full reduction.
-                    vec![Extractor::with_default(
-                        "/key",
-                        &SerPolicy::noop(),
-                        json!("def"),
-                    )],
-                    None,
-                    Validator::new(schema).unwrap(),
-                )
-            })
-            .take(3),
-        );
-
+    fn test_adaptive_chunk_sizing() {
         let alloc = Bump::new();
-        let fixtures = vec![
-            segment_fixture(
-                &[
-                    (0, json!({"key": "aaa", "v": ["apple"]}), true),
-                    (0, json!({"key": "bbb", "v": ["banana"]}), false),
-                    (1, json!({"key": "ccc", "v": ["carrot"]}), false),
-                ],
-                &alloc,
-            ),
-            segment_fixture(
-                &[
-                    (0, json!({"key": "bbb", "v": ["avocado"]}), true),
-                    (1, json!({"key": "bbb", "v": ["apricot"]}), true),
-                    (1, json!({"key": "ccc", "v": ["raisin"]}), true),
-                    (2, json!({"key": "ddd", "v": ["tomato"]}), true),
-                ],
-                &alloc,
-            ),
-            segment_fixture(
-                &[
-                    (1, json!({"key": "ccc", "v": ["dill"]}), false),
-                    (2, json!({"key": "ddd", "v": ["pickle"]}), false),
-                    (2, json!({"key": "eee", "v": ["squash"]}), false),
-                ],
-                &alloc,
-            ),
-        ];
+        // Documents are highly compressible, as each repeats the same string.
+        let fixture = (0..1000)
+            .map(|i| (0, json!({"key": i, "v": "compressible ".repeat(16)}), false))
+            .collect::<Vec<_>>();
+        let segment = segment_fixture(&fixture, &alloc);
+
+        let write = |adaptive| {
+            let mut spill = SpillWriter::with_options(
+                io::Cursor::new(Vec::new()),
+                SpillWriterOptions {
+                    chunk_target_size: 4096,
+                    adaptive,
+                },
+            )
+            .unwrap();
+            spill.write_segment(&segment).unwrap();
+            let (spill, ranges) = spill.into_parts();
+            let spill = spill.into_inner();
+
+            // Walk chunk headers to collect the uncompressed length of each chunk.
+            let (mut offset, mut raw_lens) = (ranges[0].start as usize, Vec::new());
+            while offset != ranges[0].end as usize {
+                let header = &spill[offset..offset + 8];
+                let lz4_len = u32::from_ne_bytes(header[0..4].try_into().unwrap());
+                raw_lens.push(u32::from_ne_bytes(header[4..8].try_into().unwrap()) as usize);
+                offset += 8 + lz4_len as usize;
+            }
+            (raw_lens, spill.len())
+        };
 
-        let mut spill = SpillWriter::new(io::Cursor::new(Vec::new())).unwrap();
-        for segment in fixtures {
-            spill.write_segment(&segment, 2).unwrap();
-        }
+        let (fixed_chunks, fixed_len) = write(false);
+        let (adaptive_chunks, adaptive_len) = write(true);
 
-        // Map from SpillWriter => SpillDrainer.
-        let (spill, ranges) = spill.into_parts();
-        let drainer = SpillDrainer::new(spec, spill, &ranges).unwrap();
+        // Adaptive sizing produces fewer, larger chunks and a smaller spill file.
+        assert!(adaptive_chunks.len() < fixed_chunks.len());
+        assert!(adaptive_len < fixed_len, "{adaptive_len} vs {fixed_len}");
 
-        let actual = drainer
-            .map_ok(|doc| {
-                (
-                    doc.meta.binding(),
-                    serde_json::to_value(SerPolicy::noop().on_owned(&doc.root)).unwrap(),
-                    doc.meta.front(),
-                )
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+        // Uncompressed chunks are bounded to MAX_ADAPTIVE_EXPANSION of the target,
+        // plus the one document which crossed the threshold.
+        let max_raw_len = adaptive_chunks.iter().max().unwrap();
+        assert!(*max_raw_len < (MAX_ADAPTIVE_EXPANSION * 4096) + 512);
+        assert!(*max_raw_len > 2 * fixed_chunks.iter().max().unwrap());
+    }
+
+    #[test]
+    fn test_heap_merge() {
+        let (mut drainer, _) = heap_merge_fixture();
+        let actual = drain_docs(&mut drainer);
 
         insta::assert_json_snapshot!(actual, @r###"
         [
@@ -708,29 +959,53 @@ mod test {
     }
 
     #[test]
-    fn test_drain_validation() {
-        let spec = Spec::with_bindings(
-            std::iter::repeat_with(|| {
-                let schema = build_schema(
-                    url::Url::parse("http://example/schema").unwrap(),
-                    &json!({
-                        "properties": {
-                            "key": { "type": "string" },
-                            "v": { "const": "good" },
-                        }
-                    }),
-                )
-                .unwrap();
-
-                (
-                    true, // Full reduction.
-                    vec![Extractor::new("/key", &SerPolicy::noop())],
-                    None,
-                    Validator::new(schema).unwrap(),
-                )
-            })
-            .take(1),
+    fn test_drain_stats() {
+        let (mut drainer, ranges) = heap_merge_fixture();
+        drain_docs(&mut drainer);
+
+        // Each of ten documents was written into its own chunk, and four were reduced.
+        let stats = drainer.drain_stats();
+        assert_eq!(stats.total_docs, 6);
+        assert_eq!(stats.total_reductions, 4);
+        assert_eq!(stats.chunk_reads, 10);
+        assert_eq!(
+            stats.total_bytes_read,
+            ranges.iter().map(|r| r.end - r.start).sum::<u64>()
         );
+    }
+
+    #[test]
+    fn test_rewind() {
+        let (mut drainer, _) = heap_merge_fixture();
+        let first = drain_docs(&mut drainer);
+
+        // Rewind and drain again, which produces identical documents.
+        drainer.rewind().unwrap();
+        let second = drain_docs(&mut drainer);
+        assert_eq!(first, second);
+
+        // Stats accumulate across both passes.
+        let stats = drainer.drain_stats();
+        assert_eq!(stats.total_docs, 12);
+        assert_eq!(stats.total_reductions, 8);
+        assert_eq!(stats.chunk_reads, 20);
+    }
+
+    #[test]
+    fn test_drain_validation() {
+        let spec = SpecBuilder::default()
+            .add_binding(
+                true, // Full reduction.
+                vec![Extractor::new("/key", &SerPolicy::noop())],
+                json!({
+                    "properties": {
+                        "key": { "type": "string" },
+                        "v": { "const": "good" },
+                    }
+                }),
+            )
+            .build()
+            .unwrap();
 
         let alloc = Bump::new();
 
@@ -754,9 +1029,9 @@ mod test {
             ),
         ];
 
-        let mut spill = SpillWriter::new(io::Cursor::new(Vec::new())).unwrap();
+        let mut spill = spill_writer(CHUNK_TARGET_SIZE);
         for segment in fixtures {
-            spill.write_segment(&segment, CHUNK_TARGET_SIZE).unwrap();
+            spill.write_segment(&segment).unwrap();
         }
         let (spill, ranges) = spill.into_parts();
         let mut drainer = SpillDrainer::new(spec, spill, &ranges).unwrap();
@@ -797,6 +1072,40 @@ mod test {
         assert!(drainer.next().is_none());
     }
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "was drained more than once")]
+    fn test_full_drain_once_is_checked() {
+        let spec = SpecBuilder::default()
+            .add_binding(
+                true, // Full reduction.
+                vec![Extractor::new("/key", &SerPolicy::noop())],
+                json!({}),
+            )
+            .build()
+            .unwrap();
+        let alloc = Bump::new();
+
+        // Force a violation of the drain invariant by writing a segment
+        // which isn't in sorted key order, as SpillWriter requires.
+        let segment = segment_fixture(
+            &[
+                (0, json!({"key": "aaa"}), false),
+                (0, json!({"key": "bbb"}), false),
+                (0, json!({"key": "aaa"}), false),
+            ],
+            &alloc,
+        );
+        let mut spill = spill_writer(CHUNK_TARGET_SIZE);
+        spill.write_segment(&segment).unwrap();
+        let (spill, ranges) = spill.into_parts();
+        let mut drainer = SpillDrainer::new(spec, spill, &ranges).unwrap();
+
+        assert!(drainer.next().unwrap().is_ok()); // "aaa" is drained.
+        assert!(drainer.next().unwrap().is_ok()); // "bbb" is drained.
+        _ = drainer.next(); // "aaa" is drained again, and panics.
+    }
+
     #[test]
     fn test_bumpalo_chunk_capacity() {
         let alloc = bumpalo::Bump::with_capacity(1 << 15);
@@ -816,6 +1125,102 @@ mod test {
             .join("\n")
     }
 
+    // Build a SpillDrainer over fixture segments which are reduced with
+    // a merge schema, and also return the spilled ranges of the segments.
+    fn heap_merge_fixture() -> (SpillDrainer<io::Cursor<Vec<u8>>>, Vec<Range<u64>>) {
+        let schema = json!({
+            "properties": {
+                "key": { "type": "string", "default": "def" },
+                "v": {
+                    "type": "array",
+                    "reduce": { "strategy": "append" }
+                }
+            },
+            "reduce": { "strategy": "merge" }
+        });
+        let key = vec![Extractor::with_default(
+            "/key",
+            &SerPolicy::noop(),
+            json!("def"),
+        )];
+
+        // Full reductions.
+        let spec = SpecBuilder::default()
+            .add_binding(true, key.clone(), schema.clone())
+            .add_binding(true, key.clone(), schema.clone())
+            .add_binding(true, key, schema)
+            .build()
+            .unwrap();
+
+        let alloc = Bump::new();
+        let fixtures = vec![
+            segment_fixture(
+                &[
+                    (0, json!({"key": "aaa", "v": ["apple"]}), true),
+                    (0, json!({"key": "bbb", "v": ["banana"]}), false),
+                    (1, json!({"key": "ccc", "v": ["carrot"]}), false),
+                ],
+                &alloc,
+            ),
+            segment_fixture(
+                &[
+                    (0, json!({"key": "bbb", "v": ["avocado"]}), true),
+                    (1, json!({"key": "bbb", "v": ["apricot"]}), true),
+                    (1, json!({"key": "ccc", "v": ["raisin"]}), true),
+                    (2, json!({"key": "ddd", "v": ["tomato"]}), true),
+                ],
+                &alloc,
+            ),
+            segment_fixture(
+                &[
+                    (1, json!({"key": "ccc", "v": ["dill"]}), false),
+                    (2, json!({"key": "ddd", "v": ["pickle"]}), false),
+                    (2, json!({"key": "eee", "v": ["squash"]}), false),
+                ],
+                &alloc,
+            ),
+        ];
+
+        let mut spill = spill_writer(2);
+        for segment in fixtures {
+            spill.write_segment(&segment).unwrap();
+        }
+
+        // Map from SpillWriter => SpillDrainer.
+        let (spill, ranges) = spill.into_parts();
+        let drainer = SpillDrainer::new(spec, spill, &ranges).unwrap();
+
+        (drainer, ranges)
+    }
+
+    // Drain all documents as (binding, document, front) tuples.
+    fn drain_docs<F: io::Read + io::Seek>(
+        drainer: &mut SpillDrainer<F>,
+    ) -> Vec<(usize, Value, bool)> {
+        drainer
+            .map_ok(|doc| {
+                (
+                    doc.meta.binding(),
+                    serde_json::to_value(SerPolicy::noop().on_owned(&doc.root)).unwrap(),
+                    doc.meta.front(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    // Build an in-memory SpillWriter having the given chunk target size.
+    fn spill_writer(chunk_target_size: usize) -> SpillWriter<io::Cursor<Vec<u8>>> {
+        SpillWriter::with_options(
+            io::Cursor::new(Vec::new()),
+            SpillWriterOptions {
+                chunk_target_size,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
     fn segment_fixture<'alloc>(
         fixture: &[(u32, Value, bool)],
         alloc: &'alloc bumpalo::Bump,
@@ -16,6 +16,8 @@ pub struct Extractor {
     ptr: Pointer,
     policy: SerPolicy,
     default: serde_json::Value,
+    // Is `default` also extracted for an explicit `null` at the location?
+    default_on_null: bool,
     magic: Option<Magic>,
 }
 
@@ -33,6 +35,7 @@ impl Extractor {
             ptr: Pointer::from(ptr),
             policy: policy.clone(),
             default: serde_json::Value::Null,
+            default_on_null: false,
             magic: None,
         }
     }
@@ -44,16 +47,28 @@ impl Extractor {
             ptr: Pointer::from(ptr),
             policy: policy.clone(),
             default,
+            default_on_null: false,
             magic: None,
         }
     }
 
+    /// Build an extractor for the JSON pointer.
+    /// If the location doesn't exist or is an explicit `null`,
+    /// the provided value is extracted instead.
+    pub fn with_default_on_null(ptr: &str, policy: &SerPolicy, default: serde_json::Value) -> Self {
+        Self {
+            default_on_null: true,
+            ..Self::with_default(ptr, policy, default)
+        }
+    }
+
     /// Build an extractor for the JSON pointer, which is a v1 UUID.
     pub fn for_uuid_v1_date_time(ptr: &str) -> Self {
         Self {
             ptr: Pointer::from(ptr),
             policy: SerPolicy::noop(),
             default: serde_json::Value::Null,
+            default_on_null: false,
             magic: Some(Magic::UuidV1DateTime),
         }
     }
@@ -63,6 +78,7 @@ impl Extractor {
             ptr: Pointer::empty(),
             policy: SerPolicy::noop(),
             default: serde_json::Value::Null,
+            default_on_null: false,
             magic: Some(Magic::TruncationIndicator),
         }
     }
@@ -80,8 +96,9 @@ impl Extractor {
         &'s self,
         doc: &'n N,
     ) -> Result<&'n N, Cow<'s, serde_json::Value>> {
-        let Some(node) = self.ptr.query(doc) else {
-            return Err(Cow::Borrowed(&self.default));
+        let node = match self.ptr.query(doc) {
+            Some(node) if !(self.default_on_null && matches!(node.as_node(), Node::Null)) => node,
+            _ => return Err(Cow::Borrowed(&self.default)),
         };
 
         match self.magic {
@@ -215,6 +232,30 @@ impl Extractor {
         self.extract_indicate_truncation(doc, w, indicator)
     }
 
+    /// Extract a composite key from an instance of doc::AsNode as a JSON array,
+    /// having one element for each extractor. This is a human-readable
+    /// alternative to the packed tuple representation of `extract_all`.
+    pub fn extract_all_as_json<N: AsNode>(key: &[Self], doc: &N) -> serde_json::Value {
+        let values = key
+            .iter()
+            .map(|ex| {
+                match ex.query(doc) {
+                    Ok(v) => serde_json::to_value(ex.policy.on(v)),
+                    Err(v) => serde_json::to_value(ex.policy.on(v.as_ref())),
+                }
+                .expect("serializing a document node to a Value cannot fail")
+            })
+            .collect();
+
+        serde_json::Value::Array(values)
+    }
+
+    /// Format a composite key extracted from an instance of doc::AsNode as
+    /// a JSON array string, for use in logging and error messages.
+    pub fn composite_key_to_string<N: AsNode>(key: &[Self], doc: &N) -> String {
+        Self::extract_all_as_json(key, doc).to_string()
+    }
+
     /// Compare the deep ordering of `lhs` and `rhs` with respect to a composite key.
     pub fn compare_key<L: AsNode, R: AsNode>(key: &[Self], lhs: &L, rhs: &R) -> std::cmp::Ordering {
         use std::cmp::Ordering;
@@ -330,6 +371,41 @@ mod test {
         "###);
     }
 
+    #[test]
+    fn test_extract_all_as_json() {
+        let doc = json!({
+            "id": 42,
+            "obj": {"name": "widget", "tags": ["a", "b"]},
+            "long-str": "very very long",
+        });
+        let policy = SerPolicy::noop();
+
+        let key = vec![
+            Extractor::new("/id", &policy),
+            Extractor::new("/obj/tags", &policy),
+            Extractor::with_default("/missing", &policy, json!("default")),
+        ];
+        assert_eq!(
+            Extractor::extract_all_as_json(&key, &doc),
+            json!([42, ["a", "b"], "default"]),
+        );
+        assert_eq!(
+            Extractor::composite_key_to_string(&key, &doc),
+            r#"[42,["a","b"],"default"]"#,
+        );
+
+        // Extracted values are subject to the extractor's SerPolicy.
+        let policy = SerPolicy::truncate_strings(4);
+        let key = vec![
+            Extractor::new("/long-str", &policy),
+            Extractor::new("/missing", &policy),
+        ];
+        assert_eq!(
+            Extractor::extract_all_as_json(&key, &doc),
+            json!(["very", null]),
+        );
+    }
+
     #[test]
     fn test_setting_truncation_indicator() {
         let policy = SerPolicy {
@@ -473,4 +549,32 @@ mod test {
         // Key exists at |d1| but not |d2|. Both are null (implicit and explicit).
         assert_eq!(Extractor::compare_key(&[a()], d1, d2), Ordering::Equal);
     }
+
+    #[test]
+    fn test_default_on_null() {
+        let policy = SerPolicy::noop();
+        let key = |ptr: &str| {
+            vec![
+                Extractor::new(ptr, &policy),
+                Extractor::with_default(ptr, &policy, json!("def")),
+                Extractor::with_default_on_null(ptr, &policy, json!("def")),
+            ]
+        };
+        let doc = json!({"null": null, "present": "value"});
+
+        // Absent fields extract the default (or null, if there is none).
+        assert_eq!(
+            Extractor::extract_all_as_json(&key("/absent"), &doc),
+            json!([null, "def", "def"]),
+        );
+        // Only `with_default_on_null` substitutes the default for an explicit null.
+        assert_eq!(
+            Extractor::extract_all_as_json(&key("/null"), &doc),
+            json!([null, null, "def"]),
+        );
+        assert_eq!(
+            Extractor::extract_all_as_json(&key("/present"), &doc),
+            json!(["value", "value", "value"]),
+        );
+    }
 }
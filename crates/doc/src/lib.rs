@@ -122,6 +122,10 @@ pub use shape::Shape;
 pub mod diff;
 pub use diff::diff;
 
+// RFC 6902 JSON patches may be applied to documents.
+pub mod patch;
+pub use patch::{apply_json_patch, PatchError};
+
 #[cfg(test)]
 mod test {
 
@@ -0,0 +1,315 @@
+use super::{compare, HeapNode};
+
+/// PatchError is an error encountered while applying a JSON patch.
+/// It carries the index of the failed operation within the patch.
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("patch operation {index} failed: {message}")]
+pub struct PatchError {
+    pub index: usize,
+    pub message: String,
+}
+
+/// Apply an RFC 6902 JSON `patch` to the `base` document, returning a new
+/// patched HeapNode. The patch must be an array of operations, which are applied
+/// in order. Supported operations are `add`, `remove`, `replace`, `move`, `copy`,
+/// and `test`. If any operation fails, the returned error identifies it.
+pub fn apply_json_patch<'alloc>(
+    base: &HeapNode<'alloc>,
+    patch: &serde_json::Value,
+    alloc: &'alloc bumpalo::Bump,
+) -> Result<HeapNode<'alloc>, PatchError> {
+    let Some(ops) = patch.as_array() else {
+        return Err(PatchError {
+            index: 0,
+            message: "patch is not an array of operations".to_string(),
+        });
+    };
+    let mut doc = HeapNode::from_node(base, alloc);
+
+    for (index, op) in ops.iter().enumerate() {
+        apply_operation(&mut doc, op, alloc).map_err(|message| PatchError { index, message })?;
+    }
+    Ok(doc)
+}
+
+fn apply_operation<'alloc>(
+    doc: &mut HeapNode<'alloc>,
+    op: &serde_json::Value,
+    alloc: &'alloc bumpalo::Bump,
+) -> Result<(), String> {
+    let field = |name: &str| -> Result<&serde_json::Value, String> {
+        op.get(name)
+            .ok_or_else(|| format!("operation is missing '{name}'"))
+    };
+    let pointer = |name: &str| -> Result<Vec<String>, String> {
+        match field(name)? {
+            serde_json::Value::String(ptr) => parse_pointer(ptr),
+            _ => Err(format!("operation '{name}' is not a string")),
+        }
+    };
+
+    match field("op")?.as_str() {
+        Some("add") => {
+            let value = HeapNode::from_node(field("value")?, alloc);
+            add(doc, &pointer("path")?, value, alloc)
+        }
+        Some("remove") => remove(doc, &pointer("path")?).map(|_| ()),
+        Some("replace") => {
+            let value = HeapNode::from_node(field("value")?, alloc);
+            let target = query_mut(doc, &pointer("path")?).ok_or("path does not exist")?;
+            *target = value;
+            Ok(())
+        }
+        Some("move") => {
+            let (from, path) = (pointer("from")?, pointer("path")?);
+            if path.len() > from.len() && path.starts_with(&from) {
+                return Err("cannot move a location into one of its children".to_string());
+            }
+            let value = remove(doc, &from)?;
+            add(doc, &path, value, alloc)
+        }
+        Some("copy") => {
+            let from = query(doc, &pointer("from")?).ok_or("from path does not exist")?;
+            let value = HeapNode::from_node(from, alloc);
+            add(doc, &pointer("path")?, value, alloc)
+        }
+        Some("test") => {
+            let target = query(doc, &pointer("path")?).ok_or("path does not exist")?;
+            if compare(target, field("value")?).is_eq() {
+                Ok(())
+            } else {
+                Err("tested value does not match".to_string())
+            }
+        }
+        Some(other) => Err(format!("unknown operation '{other}'")),
+        None => Err("operation 'op' is not a string".to_string()),
+    }
+}
+
+// Parse an RFC 6901 JSON pointer into its unescaped reference tokens.
+fn parse_pointer(ptr: &str) -> Result<Vec<String>, String> {
+    if ptr.is_empty() {
+        return Ok(Vec::new());
+    } else if !ptr.starts_with('/') {
+        return Err(format!("invalid JSON pointer '{ptr}'"));
+    }
+    Ok(ptr[1..]
+        .split('/')
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+// Parse an array index token, which per RFC 6901 must be only ASCII digits,
+// without a sign or leading zeros.
+fn parse_index(token: &str) -> Option<usize> {
+    if !token.bytes().all(|b| b.is_ascii_digit()) || (token.len() > 1 && token.starts_with('0')) {
+        None
+    } else {
+        token.parse().ok()
+    }
+}
+
+fn query<'n, 'alloc>(
+    mut node: &'n HeapNode<'alloc>,
+    path: &[String],
+) -> Option<&'n HeapNode<'alloc>> {
+    for token in path {
+        node = match node {
+            HeapNode::Object(fields) => {
+                let ind = fields
+                    .binary_search_by(|f| f.property.as_str().cmp(token.as_str()))
+                    .ok()?;
+                &fields[ind].value
+            }
+            HeapNode::Array(arr) => arr.get(parse_index(token)?)?,
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+fn query_mut<'n, 'alloc>(
+    mut node: &'n mut HeapNode<'alloc>,
+    path: &[String],
+) -> Option<&'n mut HeapNode<'alloc>> {
+    for token in path {
+        node = match node {
+            HeapNode::Object(fields) => {
+                let ind = fields
+                    .binary_search_by(|f| f.property.as_str().cmp(token.as_str()))
+                    .ok()?;
+                &mut fields[ind].value
+            }
+            HeapNode::Array(arr) => arr.get_mut(parse_index(token)?)?,
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+fn add<'alloc>(
+    doc: &mut HeapNode<'alloc>,
+    path: &[String],
+    value: HeapNode<'alloc>,
+    alloc: &'alloc bumpalo::Bump,
+) -> Result<(), String> {
+    let Some((last, parent)) = path.split_last() else {
+        *doc = value; // Replace the document root.
+        return Ok(());
+    };
+    match query_mut(doc, parent).ok_or("parent of path does not exist")? {
+        HeapNode::Object(fields) => {
+            *fields.insert_property(last, alloc) = value;
+        }
+        HeapNode::Array(arr) if last == "-" => arr.push(value, alloc),
+        HeapNode::Array(arr) => match parse_index(last) {
+            Some(ind) if ind <= arr.len() => arr.insert(ind, value, alloc),
+            _ => return Err(format!("array index '{last}' is out of bounds")),
+        },
+        _ => return Err("parent of path is not an object or array".to_string()),
+    }
+    Ok(())
+}
+
+fn remove<'alloc>(doc: &mut HeapNode<'alloc>, path: &[String]) -> Result<HeapNode<'alloc>, String> {
+    let Some((last, parent)) = path.split_last() else {
+        return Err("cannot remove the document root".to_string());
+    };
+    match query_mut(doc, parent) {
+        Some(HeapNode::Object(fields)) => fields.remove_property(last).map(|field| field.value),
+        Some(HeapNode::Array(arr)) => match parse_index(last) {
+            Some(ind) if ind < arr.len() => Some(arr.remove(ind)),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| "path does not exist".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SerPolicy;
+    use serde_json::json;
+
+    #[test]
+    fn test_patch_matches_reference() {
+        let base = json!({
+            "name": "widget",
+            "tags": ["a", "b", "c"],
+            "dims": {"h": 1, "w": 2},
+            "stale": true,
+            "a/b": {"c~d": 1},
+        });
+        let patch = json!([
+            {"op": "test", "path": "/name", "value": "widget"},
+            {"op": "add", "path": "/price", "value": 9.99},
+            {"op": "add", "path": "/tags/1", "value": "x"},
+            {"op": "add", "path": "/tags/-", "value": "z"},
+            {"op": "remove", "path": "/stale"},
+            {"op": "replace", "path": "/dims/h", "value": 10},
+            {"op": "move", "from": "/dims/w", "path": "/width"},
+            {"op": "copy", "from": "/tags", "path": "/dims/tags"},
+            {"op": "replace", "path": "/a~1b/c~0d", "value": {"nested": [1, 2]}},
+            {"op": "test", "path": "/width", "value": 2},
+        ]);
+
+        let alloc = HeapNode::new_allocator();
+        let base_node = HeapNode::from_node(&base, &alloc);
+        let patched = apply_json_patch(&base_node, &patch, &alloc).unwrap();
+        let patched = serde_json::to_value(SerPolicy::noop().on(&patched)).unwrap();
+
+        let mut expect = base.clone();
+        json_patch::patch(
+            &mut expect,
+            &serde_json::from_value::<json_patch::Patch>(patch).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(patched, expect);
+        assert_eq!(
+            patched,
+            json!({
+                "name": "widget",
+                "price": 9.99,
+                "tags": ["a", "x", "b", "c", "z"],
+                "dims": {"h": 10, "tags": ["a", "x", "b", "c", "z"]},
+                "width": 2,
+                "a/b": {"c~d": {"nested": [1, 2]}},
+            })
+        );
+        // The base document is unmodified.
+        assert_eq!(
+            serde_json::to_value(SerPolicy::noop().on(&base_node)).unwrap(),
+            base
+        );
+    }
+
+    #[test]
+    fn test_patch_errors() {
+        let alloc = HeapNode::new_allocator();
+        let base = HeapNode::from_node(&json!({"a": {"b": 1}, "arr": [1]}), &alloc);
+
+        let cases = [
+            (
+                json!([{"op": "test", "path": "/a/b", "value": 1}, {"op": "test", "path": "/a/b", "value": 2}]),
+                (1, "tested value does not match"),
+            ),
+            (
+                json!([{"op": "add", "path": "/missing/child", "value": 1}]),
+                (0, "parent of path does not exist"),
+            ),
+            (
+                json!([{"op": "remove", "path": "/a/c"}]),
+                (0, "path does not exist"),
+            ),
+            (
+                json!([{"op": "remove", "path": "/arr/1"}]),
+                (0, "path does not exist"),
+            ),
+            (
+                json!([{"op": "add", "path": "/arr/2", "value": 1}]),
+                (0, "array index '2' is out of bounds"),
+            ),
+            (
+                json!([{"op": "add", "path": "/arr/+0", "value": 1}]),
+                (0, "array index '+0' is out of bounds"),
+            ),
+            (
+                json!([{"op": "remove", "path": "/arr/00"}]),
+                (0, "path does not exist"),
+            ),
+            (
+                json!([{"op": "test", "path": "/arr/+0", "value": 1}]),
+                (0, "path does not exist"),
+            ),
+            (
+                json!([{"op": "move", "from": "/a", "path": "/a/b/c"}]),
+                (0, "cannot move a location into one of its children"),
+            ),
+            (
+                json!([{"op": "frobulate", "path": "/a"}]),
+                (0, "unknown operation 'frobulate'"),
+            ),
+            (
+                json!([{"op": "add", "path": "a", "value": 1}]),
+                (0, "invalid JSON pointer 'a'"),
+            ),
+            (
+                json!([{"op": "replace", "path": "/a"}]),
+                (0, "operation is missing 'value'"),
+            ),
+        ];
+
+        for (patch, (index, message)) in cases {
+            assert_eq!(
+                apply_json_patch(&base, &patch, &alloc).unwrap_err(),
+                PatchError {
+                    index,
+                    message: message.to_string()
+                },
+                "patch: {patch}"
+            );
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! This module checks whether documents of a prior Shape of a schema
+//! continue to be valid under an updated Shape of that schema.
+use super::*;
+use json::{Location, Number};
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum CompatibilityIssue {
+    #[error("'{0}' was required, but has been removed")]
+    RequiredRemoved(String),
+    #[error(
+        "'{ptr}' had types {}, but now has only types {}",
+        type_set_display(*.old),
+        type_set_display(*.new)
+    )]
+    TypeNarrowed {
+        ptr: String,
+        old: types::Set,
+        new: types::Set,
+    },
+    #[error(
+        "'{ptr}' now has minimum {new}, which is more restrictive than the prior minimum {}",
+        bound_display(.old)
+    )]
+    MinimumAdded {
+        ptr: String,
+        old: Option<Number>,
+        new: Number,
+    },
+    #[error(
+        "'{ptr}' now has maximum {new}, which is more restrictive than the prior maximum {}",
+        bound_display(.old)
+    )]
+    MaximumAdded {
+        ptr: String,
+        old: Option<Number>,
+        new: Number,
+    },
+}
+
+// Display an optional numeric bound, or "none" if there isn't one.
+fn bound_display(bound: &Option<Number>) -> String {
+    match bound {
+        Some(bound) => bound.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+/// Check the forward compatibility of `new` with `old`: whether documents
+/// which were written under `old` may be invalid under `new`. Properties
+/// are compared by name, recursively. An empty result means no issues
+/// were found, as when only optional properties are added.
+pub fn check_forward_compatibility(old: &Shape, new: &Shape) -> Vec<CompatibilityIssue> {
+    let mut out = Vec::new();
+    check_inner(Location::Root, old, new, &mut out);
+    out
+}
+
+fn check_inner(loc: Location, old: &Shape, new: &Shape, out: &mut Vec<CompatibilityIssue>) {
+    let narrowed = old.type_ - new.type_;
+    if narrowed != types::INVALID {
+        out.push(CompatibilityIssue::TypeNarrowed {
+            ptr: loc.pointer_str().to_string(),
+            old: old.type_,
+            new: new.type_,
+        });
+    }
+
+    // Bounds only constrain numbers, and they're moot if numbers aren't allowed.
+    if new.type_.overlaps(types::INT_OR_FRAC) {
+        let (old_num, new_num) = (&old.numeric, &new.numeric);
+
+        match (&old_num.minimum, &new_num.minimum) {
+            (None, Some(min)) => out.push(CompatibilityIssue::MinimumAdded {
+                ptr: loc.pointer_str().to_string(),
+                old: None,
+                new: *min,
+            }),
+            (Some(prior), Some(min)) if min > prior => out.push(CompatibilityIssue::MinimumAdded {
+                ptr: loc.pointer_str().to_string(),
+                old: Some(*prior),
+                new: *min,
+            }),
+            _ => (),
+        }
+        match (&old_num.maximum, &new_num.maximum) {
+            (None, Some(max)) => out.push(CompatibilityIssue::MaximumAdded {
+                ptr: loc.pointer_str().to_string(),
+                old: None,
+                new: *max,
+            }),
+            (Some(prior), Some(max)) if max < prior => out.push(CompatibilityIssue::MaximumAdded {
+                ptr: loc.pointer_str().to_string(),
+                old: Some(*prior),
+                new: *max,
+            }),
+            _ => (),
+        }
+    }
+
+    for old_prop in old.object.properties.iter() {
+        let loc = loc.push_prop(&old_prop.name);
+
+        match new
+            .object
+            .properties
+            .iter()
+            .find(|new_prop| new_prop.name == old_prop.name)
+        {
+            Some(new_prop) => check_inner(loc, &old_prop.shape, &new_prop.shape, out),
+            None if old_prop.is_required => {
+                out.push(CompatibilityIssue::RequiredRemoved(
+                    loc.pointer_str().to_string(),
+                ));
+            }
+            None => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_forward_compatibility, shape_from, CompatibilityIssue};
+    use json::schema::types;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_breaking_changes() {
+        let old = shape_from(
+            r#"
+        type: object
+        properties:
+            id: {type: [string, integer]}
+            name: {type: string}
+            count: {type: integer, minimum: 0}
+            price: {type: number}
+            nested:
+                type: object
+                properties:
+                    removed: {type: string}
+                required: [removed]
+        required: [id, name, nested]
+        "#,
+        );
+        let new = shape_from(
+            r#"
+        type: object
+        properties:
+            id: {type: string}
+            count: {type: integer, minimum: 10}
+            price: {type: number, maximum: 1000}
+            nested:
+                type: object
+        required: [id, nested]
+        "#,
+        );
+
+        assert_eq!(
+            check_forward_compatibility(&old, &new),
+            vec![
+                CompatibilityIssue::MinimumAdded {
+                    ptr: "/count".to_string(),
+                    old: Some(json::Number::Unsigned(0)),
+                    new: json::Number::Unsigned(10),
+                },
+                CompatibilityIssue::TypeNarrowed {
+                    ptr: "/id".to_string(),
+                    old: types::STRING | types::INTEGER,
+                    new: types::STRING,
+                },
+                CompatibilityIssue::RequiredRemoved("/name".to_string()),
+                CompatibilityIssue::RequiredRemoved("/nested/removed".to_string()),
+                CompatibilityIssue::MaximumAdded {
+                    ptr: "/price".to_string(),
+                    old: None,
+                    new: json::Number::Unsigned(1000),
+                },
+            ]
+        );
+
+        let messages = check_forward_compatibility(&old, &new)
+            .iter()
+            .filter(|issue| !matches!(issue, CompatibilityIssue::RequiredRemoved(_)))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "'/count' now has minimum 10, which is more restrictive than the prior minimum 0",
+                "'/id' had types integer, string, but now has only types string",
+                "'/price' now has maximum 1000, which is more restrictive than the prior maximum none",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compatible_changes() {
+        let old = shape_from(
+            r#"
+        type: object
+        properties:
+            id: {type: string}
+            count: {type: integer, minimum: 10}
+            dropped: {type: string}
+        required: [id]
+        "#,
+        );
+        let new = shape_from(
+            r#"
+        type: object
+        properties:
+            id: {type: [string, integer]}
+            count: {type: number, minimum: 0}
+            added: {type: string}
+        required: [id]
+        "#,
+        );
+
+        assert_eq!(check_forward_compatibility(&old, &new), vec![]);
+        assert_eq!(check_forward_compatibility(&old, &old), vec![]);
+    }
+}
@@ -9,13 +9,29 @@ pub enum Error {
     ImpossibleMustExist(String),
     #[error("'{0}' has reduction strategy, but its parent does not")]
     ChildWithoutParentReduction(String),
-    #[error("{0} has 'sum' reduction strategy, restricted to numbers, but has types {1:?}")]
+    #[error(
+        "{} has 'sum' reduction strategy, restricted to numbers, but has types {}",
+        .0,
+        type_set_display(*.1)
+    )]
     SumNotNumber(String, types::Set),
     #[error(
-        "{0} has 'merge' reduction strategy, restricted to objects & arrays, but has types {1:?}"
+        "{} has 'merge' reduction strategy, restricted to objects & arrays, but has types {}",
+        .0,
+        type_set_display(*.1)
     )]
     MergeNotObjectOrArray(String, types::Set),
-    #[error("{0} has 'set' reduction strategy, restricted to objects, but has types {1:?}")]
+    #[error(
+        "{} has 'append' reduction strategy, restricted to arrays (or null), but has types {}",
+        .0,
+        type_set_display(*.1)
+    )]
+    AppendNotArray(String, types::Set),
+    #[error(
+        "{} has 'set' reduction strategy, restricted to objects, but has types {}",
+        .0,
+        type_set_display(*.1)
+    )]
     SetNotObject(String, types::Set),
     #[error(
         "{0} location's parent has 'set' reduction strategy, restricted to 'add'/'remove'/'intersect' properties"
@@ -96,6 +112,14 @@ impl Shape {
                 self.type_,
             ));
         }
+        if matches!(self.reduction, Reduction::Strategy(Strategy::Append))
+            && self.type_ - (types::ARRAY | types::NULL) != types::INVALID
+        {
+            out.push(Error::AppendNotArray(
+                loc.pointer_str().to_string(),
+                self.type_,
+            ));
+        }
         if matches!(self.reduction, Reduction::Strategy(Strategy::Set(_))) {
             if self.type_ != types::OBJECT {
                 out.push(Error::SetNotObject(
@@ -149,6 +173,13 @@ mod test {
                 reduce: {strategy: sum}
                 type: [number, string]
 
+            append-wrong-type:
+                reduce: {strategy: append}
+                type: integer
+            append-array-or-null:
+                reduce: {strategy: append}
+                type: [array, "null"]
+
             must-exist-but-cannot: false
             may-not-exist: false
 
@@ -205,6 +236,7 @@ mod test {
                 Error::SetNotObject("/0".to_owned(), types::ANY),
                 Error::SetInvalidProperty("/-/whoops1".to_owned()),
                 Error::SetInvalidProperty("/-/whoops2".to_owned()),
+                Error::AppendNotArray("/append-wrong-type".to_owned(), types::INTEGER),
                 Error::ImpossibleMustExist("/must-exist-but-cannot".to_owned()),
                 Error::ImpossibleMustExist("/nested-array/1".to_owned()),
                 Error::SumNotNumber(
@@ -4,6 +4,7 @@ use serde_json::Value;
 use std::collections::BTreeMap;
 use url::Url;
 
+pub mod compatibility;
 mod inference;
 pub mod inspections;
 mod intersect;
@@ -201,6 +202,74 @@ impl Shape {
             string: StringShape::new(),
         }
     }
+
+    /// Describe the Shape as a brief, human-readable summary of its types
+    /// and their notable constraints, such as `string (format: date-time)`.
+    pub fn describe(&self) -> String {
+        let mut details = Vec::new();
+
+        if let Some(enum_) = &self.enum_ {
+            details.push(format!("enum of {} values", enum_.len()));
+        }
+        if self.type_.overlaps(types::STRING) {
+            if let Some(format) = &self.string.format {
+                details.push(format!("format: {}", format.to_string()));
+            }
+            if self.string.min_length != 0 {
+                details.push(format!("minLength: {}", self.string.min_length));
+            }
+            if let Some(max_length) = self.string.max_length {
+                details.push(format!("maxLength: {max_length}"));
+            }
+        }
+        if self.type_.overlaps(types::INT_OR_FRAC) {
+            if let Some(minimum) = &self.numeric.minimum {
+                details.push(format!("minimum: {minimum}"));
+            }
+            if let Some(maximum) = &self.numeric.maximum {
+                details.push(format!("maximum: {maximum}"));
+            }
+        }
+        if self.type_.overlaps(types::ARRAY) {
+            if self.array.min_items != 0 {
+                details.push(format!("minItems: {}", self.array.min_items));
+            }
+            if let Some(max_items) = self.array.max_items {
+                details.push(format!("maxItems: {max_items}"));
+            }
+        }
+        if self.type_.overlaps(types::OBJECT) && !self.object.properties.is_empty() {
+            let names = |required: bool| {
+                self.object
+                    .properties
+                    .iter()
+                    .filter(move |prop| !required || prop.is_required)
+                    .map(|prop| &*prop.name)
+                    .collect::<Vec<_>>()
+            };
+            details.push(format!("properties: {}", names(false).join(", ")));
+
+            let required = names(true);
+            if !required.is_empty() {
+                details.push(format!("required: {}", required.join(", ")));
+            }
+        }
+
+        if details.is_empty() {
+            type_set_display(self.type_)
+        } else {
+            format!("{} ({})", type_set_display(self.type_), details.join("; "))
+        }
+    }
+}
+
+/// Display a set of types as their comma-separated names, like "integer, string",
+/// for use within user-facing messages. An empty set is displayed as "none".
+pub fn type_set_display(ts: types::Set) -> String {
+    if ts == types::INVALID {
+        return "none".to_string();
+    }
+    ts.iter().collect::<Vec<_>>().join(", ")
 }
 
 // Returns true if the text is a match for the given regex. This function exists primarily so we
@@ -277,4 +346,56 @@ mod test {
         assert_eq!(std::mem::size_of::<ArrayShape>(), 48);
         assert_eq!(std::mem::size_of::<Shape>(), 328);
     }
+
+    #[test]
+    fn test_type_set_display() {
+        use super::type_set_display;
+        use json::schema::types;
+
+        assert_eq!(type_set_display(types::STRING), "string");
+        assert_eq!(
+            type_set_display(types::INTEGER | types::STRING | types::NULL),
+            "integer, null, string"
+        );
+        assert_eq!(type_set_display(types::INVALID), "none");
+    }
+
+    #[test]
+    fn test_describe() {
+        use super::{shape_from, Shape};
+
+        let shape = shape_from(
+            r#"
+        type: object
+        properties:
+            id: {type: [integer, string], format: uuid, maxLength: 36}
+            count: {type: integer, minimum: 0}
+            tags: {type: array, minItems: 1}
+            kind: {enum: [a, b]}
+        required: [id]
+        "#,
+        );
+        assert_eq!(
+            shape.describe(),
+            "object (properties: count, id, kind, tags; required: id)"
+        );
+
+        let describe = |name: &str| {
+            let prop = shape.object.properties.iter().find(|p| &*p.name == name);
+            prop.unwrap().shape.describe()
+        };
+        assert_eq!(
+            describe("id"),
+            "integer, string (format: uuid; maxLength: 36)"
+        );
+        assert_eq!(describe("count"), "integer (minimum: 0)");
+        assert_eq!(describe("tags"), "array (minItems: 1)");
+        assert_eq!(describe("kind"), "string (enum of 2 values)");
+
+        assert_eq!(
+            Shape::anything().describe(),
+            "array, boolean, null, number, object, string"
+        );
+        assert_eq!(Shape::nothing().describe(), "none");
+    }
 }
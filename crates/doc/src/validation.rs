@@ -1,4 +1,4 @@
-use super::{reduce, walker::walk_document, Annotation, AsNode, SerPolicy};
+use super::{reduce, walker::walk_document, Annotation, AsNode, HeapNode, SerPolicy, Shape};
 use json::validator::Context;
 use std::pin::Pin;
 
@@ -47,6 +47,8 @@ pub struct Validator {
     inner: json::validator::Validator<'static, Annotation, SpanContext>,
     index: Pin<Box<SchemaIndex<'static>>>,
     schemas: Pin<Box<[Schema]>>,
+    // Shapes inferred from schemas by validate_with_defaults(), keyed on schema URL.
+    shapes: std::collections::HashMap<url::Url, Shape>,
 }
 
 impl Validator {
@@ -82,6 +84,7 @@ impl Validator {
             inner: json::validator::Validator::new(index_static),
             index,
             schemas,
+            shapes: Default::default(),
         })
     }
 
@@ -102,13 +105,7 @@ impl Validator {
         schema: Option<&'v url::Url>,
         document: &'doc N,
     ) -> Result<Validation<'static, 'doc, 'v, N>, json::schema::index::Error> {
-        let effective_schema = match schema {
-            Some(schema) => schema,
-            None if self.schemas.len() == 1 => &self.schemas[0].curi,
-            None => {
-                panic!("root_curi() may only be used with Validators having a single root schema")
-            }
-        };
+        let effective_schema = effective_schema(&self.schemas, schema);
         self.inner.prepare(effective_schema)?;
 
         let root = json::Location::Root;
@@ -121,6 +118,72 @@ impl Validator {
             validator: &mut self.inner,
         })
     }
+
+    /// Validate the document against the given schema, as with validate(), and if it's
+    /// valid then return it as a HeapNode having default values applied.
+    /// Defaults are applied for each missing property of an object location which has
+    /// a valid `default` annotation, and nested objects of the document are defaulted
+    /// in turn. The Shape of each schema is inferred on first use, and then cached.
+    pub fn validate_with_defaults<'alloc>(
+        &mut self,
+        schema: Option<&url::Url>,
+        document: &serde_json::Value,
+        alloc: &'alloc bumpalo::Bump,
+    ) -> Result<Result<HeapNode<'alloc>, FailedValidation>, json::schema::index::Error> {
+        let curi = effective_schema(&self.schemas, schema).clone();
+        if let Err(err) = self.validate(Some(&curi), document)?.ok() {
+            return Ok(Err(err));
+        }
+        let shape = match self.shapes.entry(curi) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let shape = Shape::infer(self.index.must_fetch(entry.key())?, &self.index);
+                entry.insert(shape)
+            }
+        };
+
+        let mut node = HeapNode::from_node(document, alloc);
+        apply_defaults(&mut node, shape, alloc);
+        Ok(Ok(node))
+    }
+}
+
+// Resolve the schema to validate against: the given `schema`, or else the root
+// schema of a Validator which has only one.
+fn effective_schema<'s>(schemas: &'s [Schema], schema: Option<&'s url::Url>) -> &'s url::Url {
+    match schema {
+        Some(schema) => schema,
+        None if schemas.len() == 1 => &schemas[0].curi,
+        None => {
+            panic!("root_curi() may only be used with Validators having a single root schema")
+        }
+    }
+}
+
+// Apply default values of the Shape's object properties which are missing
+// from the `node`, and recurse into properties which are present.
+fn apply_defaults<'alloc>(
+    node: &mut HeapNode<'alloc>,
+    shape: &Shape,
+    alloc: &'alloc bumpalo::Bump,
+) {
+    let HeapNode::Object(fields) = node else {
+        return;
+    };
+
+    for prop in shape.object.properties.iter() {
+        let property = prop.name.as_ref();
+
+        match fields.binary_search_by(|field| field.property.as_str().cmp(property)) {
+            Ok(ind) => apply_defaults(&mut fields[ind].value, &prop.shape, alloc),
+            // Defaults which don't validate against their schema are not applied.
+            Err(_) => {
+                if let Some((value, None)) = prop.shape.default.as_deref() {
+                    *fields.insert_property(property, alloc) = HeapNode::from_node(value, alloc);
+                }
+            }
+        }
+    }
 }
 
 /// Validation represents the outcome of a document validation.
@@ -231,3 +294,80 @@ impl<'schema> Valid<'schema, '_> {
         idx
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_with_defaults() {
+        let curi = url::Url::parse("http://example/schema").unwrap();
+        let schema = build_schema(
+            curi,
+            &json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer"},
+                    "status": {"type": "string", "default": "pending"},
+                    "nested": {
+                        "type": "object",
+                        "properties": {
+                            "count": {"type": "integer", "default": 0},
+                            "bad": {"type": "integer", "default": "not-an-integer"},
+                        },
+                    },
+                    "absent": {
+                        "type": "object",
+                        "properties": {"inner": {"default": true}},
+                    },
+                },
+                "required": ["id"],
+            }),
+        )
+        .unwrap();
+        let mut validator = Validator::new(schema).unwrap();
+        let alloc = HeapNode::new_allocator();
+
+        let node = validator
+            .validate_with_defaults(None, &json!({"id": 42, "nested": {}}), &alloc)
+            .unwrap()
+            .unwrap();
+
+        // Defaults are applied to missing properties, including within nested objects.
+        // Invalid defaults, and properties of missing parent objects, are not applied.
+        assert_eq!(
+            serde_json::to_value(SerPolicy::noop().on(&node)).unwrap(),
+            json!({"id": 42, "status": "pending", "nested": {"count": 0}}),
+        );
+
+        // Present properties are not replaced by defaults.
+        let node = validator
+            .validate_with_defaults(None, &json!({"id": 1, "status": "done"}), &alloc)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(SerPolicy::noop().on(&node)).unwrap(),
+            json!({"id": 1, "status": "done"}),
+        );
+        // The schema's Shape was inferred once, and is re-used.
+        assert_eq!(validator.shapes.len(), 1);
+
+        // Invalid documents fail validation, and aren't defaulted.
+        assert!(validator
+            .validate_with_defaults(None, &json!({"status": "done"}), &alloc)
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "single root schema")]
+    fn test_validate_with_defaults_requires_a_single_root() {
+        let schemas = ["http://example/one", "http://example/two"]
+            .map(|curi| build_schema(url::Url::parse(curi).unwrap(), &json!(true)).unwrap());
+        let mut validator = Validator::new_from_iter(schemas).unwrap();
+        let alloc = HeapNode::new_allocator();
+
+        _ = validator.validate_with_defaults(None, &json!({}), &alloc);
+    }
+}
@@ -30,6 +30,12 @@ pub enum Command {
     /// You can find this token within Flow UI dashboard under "Admin"
     /// (https://dashboard.estuary.dev/admin/api).
     Token(Token),
+    /// Sign out of Flow, removing stored credentials.
+    ///
+    /// Revokes the refresh token created by `auth login` or `auth token`,
+    /// and removes all credentials from the current profile. Credentials
+    /// are removed even if the refresh token couldn't be revoked.
+    Logout,
     /// Work with authorization roles and grants.
     ///
     /// Roles are prefixes of the Flow catalog namespace.
@@ -80,12 +86,48 @@ impl Auth {
                 println!("Configured access token.");
                 Ok(())
             }
+            Command::Logout => do_logout(ctx).await,
             Command::Roles(roles) => roles.run(ctx).await,
             Command::DataPlaneAccessToken(args) => do_data_plane_access_token(ctx, args).await,
         }
     }
 }
 
+async fn do_logout(ctx: &mut crate::CliContext) -> anyhow::Result<()> {
+    let Some(api) = &ctx.config().api else {
+        println!("Not logged in.");
+        return Ok(());
+    };
+
+    if let Some(refresh_token) = &api.refresh_token {
+        let id = refresh_token.id.clone();
+
+        if let Err(err) = revoke_refresh_token(ctx, &id).await {
+            tracing::warn!(error = ?err, "failed to revoke refresh token (credentials will be removed anyway)");
+            eprintln!("Warning: failed to revoke your refresh token: {err:#}");
+            eprintln!("Your credentials have been removed from this profile anyway.");
+        }
+    }
+    ctx.config_mut().api = None;
+
+    println!("Logged out successfully");
+    Ok(())
+}
+
+async fn revoke_refresh_token(ctx: &mut crate::CliContext, id: &str) -> anyhow::Result<()> {
+    crate::api_exec::<Vec<serde_json::Value>>(
+        ctx.controlplane_client()
+            .await?
+            .from("refresh_tokens")
+            .select("id")
+            .eq("id", id)
+            .delete(),
+    )
+    .await
+    .context("revoking refresh token")?;
+    Ok(())
+}
+
 async fn do_login(ctx: &mut crate::CliContext) -> anyhow::Result<()> {
     use crossterm::tty::IsTty;
 
@@ -142,3 +184,83 @@ async fn do_data_plane_access_token(
     println!("{}", access.auth_token);
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use warp::Filter;
+
+    // Serve a control-plane API which responds to refresh token deletions with
+    // `status`, returning its URL and the queries of received deletions.
+    fn serve(status: warp::http::StatusCode) -> (String, Arc<Mutex<Vec<String>>>) {
+        let deletions = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = deletions.clone();
+        let api = warp::delete()
+            .and(warp::path!("refresh_tokens"))
+            .and(warp::query::raw())
+            .map(move |query: String| {
+                recorded.lock().unwrap().push(query);
+                warp::reply::with_status(warp::reply::json(&serde_json::json!([])), status)
+            });
+
+        let (addr, server) = warp::serve(api).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (format!("http://{addr}"), deletions)
+    }
+
+    fn logged_in(api_url: &str) -> crate::CliContext {
+        let config = crate::config::Config {
+            api: Some(crate::config::API {
+                endpoint: url::Url::parse(api_url).unwrap(),
+                public_token: "public".to_string(),
+                access_token: "access".to_string(),
+                refresh_token: Some(crate::config::RefreshToken {
+                    id: "a-token-id".to_string(),
+                    secret: "secret".to_string(),
+                }),
+            }),
+            ..Default::default()
+        };
+        crate::CliContext {
+            config,
+            output: Default::default(),
+            controlplane_client: Some(controlplane::Client::new(postgrest::Postgrest::new(
+                api_url,
+            ))),
+            journal_client_pool: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logout_revokes_refresh_token() {
+        let (api_url, deletions) = serve(warp::http::StatusCode::OK);
+        let mut ctx = logged_in(&api_url);
+
+        do_logout(&mut ctx).await.unwrap();
+
+        assert!(ctx.config().api.is_none());
+        let deletions = deletions.lock().unwrap();
+        assert_eq!(deletions.len(), 1);
+        assert!(
+            deletions[0].contains("id=eq.a-token-id"),
+            "{}",
+            deletions[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_logout_when_revocation_fails() {
+        let (api_url, deletions) = serve(warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+        let mut ctx = logged_in(&api_url);
+
+        assert!(revoke_refresh_token(&mut ctx, "a-token-id").await.is_err());
+
+        // Credentials are removed even though the token couldn't be revoked.
+        do_logout(&mut ctx).await.unwrap();
+
+        assert!(ctx.config().api.is_none());
+        assert_eq!(deletions.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,215 @@
+use super::{fetch_live_specs, List, NameSelector, SpecTypeSelector};
+use crate::{collection::CollectionJournalSelector, dataplane};
+use anyhow::Context;
+use journal_client::{broker, list::list_journals};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, clap::Args)]
+#[clap(rename_all = "kebab-case")]
+pub struct CheckDrift {
+    #[clap(flatten)]
+    pub name_selector: NameSelector,
+}
+
+/// A field of a live journal which differs from its catalog specification.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Drift {
+    journal: String,
+    field: String,
+    catalog_value: Value,
+    live_value: Value,
+}
+
+impl crate::output::CliOutput for Drift {
+    type TableAlt = ();
+    type CellValue = String;
+
+    fn table_headers(_alt: Self::TableAlt) -> Vec<&'static str> {
+        vec!["Journal", "Field", "Catalog Value", "Live Value"]
+    }
+
+    fn into_table_row(self, _alt: Self::TableAlt) -> Vec<Self::CellValue> {
+        vec![
+            self.journal,
+            self.field,
+            self.catalog_value.to_string(),
+            self.live_value.to_string(),
+        ]
+    }
+}
+
+pub async fn do_check_drift(ctx: &mut crate::CliContext, args: &CheckDrift) -> anyhow::Result<()> {
+    #[derive(Deserialize)]
+    struct Row {
+        catalog_name: String,
+        built_spec: Option<proto_flow::flow::CollectionSpec>,
+    }
+
+    let list = List {
+        name_selector: args.name_selector.clone(),
+        type_selector: SpecTypeSelector {
+            collections: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let rows = fetch_live_specs::<Row>(
+        ctx.controlplane_client().await?,
+        &list,
+        vec!["catalog_name", "built_spec"],
+    )
+    .await?;
+
+    let mut client = dataplane::journal_client_for(
+        ctx.controlplane_client().await?,
+        ctx.journal_client_pool(),
+        rows.iter().map(|row| row.catalog_name.clone()).collect(),
+        dataplane::DEFAULT_MAX_MESSAGE_SIZE,
+    )
+    .await?;
+
+    let mut drifts = Vec::new();
+    for Row {
+        catalog_name,
+        built_spec,
+    } in rows
+    {
+        let Some(template) = built_spec.and_then(|spec| spec.partition_template) else {
+            tracing::warn!(%catalog_name, "collection has no built partition template (skipping)");
+            continue;
+        };
+        let selector = CollectionJournalSelector {
+            collection: catalog_name.clone(),
+            partitions: None,
+        };
+        let journals = list_journals(&mut client, &selector.build_label_selector())
+            .await
+            .with_context(|| format!("listing journals of '{catalog_name}'"))?;
+
+        for journal in journals {
+            drifts.extend(diff_journal_spec(&template, &journal)?);
+        }
+    }
+
+    ctx.write_all(drifts, ())
+}
+
+/// Compare a live journal against its catalog partition template,
+/// returning each field of the journal which differs from the template.
+/// Journal names and labels always differ from the template, and are not compared.
+pub fn diff_journal_spec(
+    template: &broker::JournalSpec,
+    live: &broker::JournalSpec,
+) -> anyhow::Result<Vec<Drift>> {
+    let strip = |spec: &broker::JournalSpec| -> anyhow::Result<Value> {
+        let mut value = serde_json::to_value(spec)?;
+        if let Value::Object(map) = &mut value {
+            map.remove("name");
+            map.remove("labels");
+        }
+        Ok(value)
+    };
+
+    let mut out = Vec::new();
+    diff_values(
+        &live.name,
+        &mut String::new(),
+        &strip(template)?,
+        &strip(live)?,
+        &mut out,
+    );
+    Ok(out)
+}
+
+fn diff_values(
+    journal: &str,
+    ptr: &mut String,
+    catalog: &Value,
+    live: &Value,
+    out: &mut Vec<Drift>,
+) {
+    match (catalog, live) {
+        (Value::Object(catalog), Value::Object(live)) => {
+            let mut props = catalog.keys().chain(live.keys()).collect::<Vec<_>>();
+            props.sort();
+            props.dedup();
+
+            for prop in props {
+                let len = ptr.len();
+                ptr.push('/');
+                ptr.push_str(prop);
+
+                diff_values(
+                    journal,
+                    ptr,
+                    catalog.get(prop).unwrap_or(&Value::Null),
+                    live.get(prop).unwrap_or(&Value::Null),
+                    out,
+                );
+                ptr.truncate(len);
+            }
+        }
+        (catalog, live) if catalog != live => out.push(Drift {
+            journal: journal.to_string(),
+            field: ptr.clone(),
+            catalog_value: catalog.clone(),
+            live_value: live.clone(),
+        }),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use broker::journal_spec::Fragment;
+
+    #[test]
+    fn test_journal_spec_drift() {
+        let template = broker::JournalSpec {
+            name: "acme/events/clicks".to_string(),
+            replication: 3,
+            max_append_rate: 4194304,
+            fragment: Some(Fragment {
+                length: 536870912,
+                compression_codec: 4,
+                stores: vec!["gs://a-bucket/".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut live = template.clone();
+        live.name = "acme/events/clicks/pivot=00".to_string();
+        live.labels = Some(broker::LabelSet {
+            labels: vec![broker::Label {
+                name: "estuary.dev/collection".to_string(),
+                value: "acme/events/clicks".to_string(),
+            }],
+        });
+        assert_eq!(diff_journal_spec(&template, &live).unwrap(), vec![]);
+
+        live.max_append_rate = 1024;
+        live.fragment.as_mut().unwrap().stores = vec!["s3://other-bucket/".to_string()];
+
+        assert_eq!(
+            diff_journal_spec(&template, &live).unwrap(),
+            vec![
+                Drift {
+                    journal: "acme/events/clicks/pivot=00".to_string(),
+                    field: "/fragment/stores".to_string(),
+                    catalog_value: serde_json::json!(["gs://a-bucket/"]),
+                    live_value: serde_json::json!(["s3://other-bucket/"]),
+                },
+                Drift {
+                    journal: "acme/events/clicks/pivot=00".to_string(),
+                    field: "/maxAppendRate".to_string(),
+                    catalog_value: serde_json::json!("4194304"),
+                    live_value: serde_json::json!("1024"),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use super::{collect_specs, fetch_live_specs, List, LiveSpecRow, NameSelector, SpecTypeSelector};
+use anyhow::Context;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+#[clap(rename_all = "kebab-case")]
+pub struct Import {
+    #[clap(flatten)]
+    pub name_selector: NameSelector,
+    /// Directory into which collection specifications are written.
+    #[clap(long)]
+    pub output_dir: PathBuf,
+    /// Leave existing files unmodified, rather than over-writing them.
+    #[clap(long)]
+    pub no_overwrite: bool,
+}
+
+pub async fn do_import(ctx: &mut crate::CliContext, args: &Import) -> anyhow::Result<()> {
+    let list = List {
+        name_selector: args.name_selector.clone(),
+        type_selector: SpecTypeSelector {
+            collections: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let live_specs = fetch_live_specs::<LiveSpecRow>(
+        ctx.controlplane_client().await?,
+        &list,
+        vec!["catalog_name", "id", "updated_at", "spec_type", "spec"],
+    )
+    .await?;
+    tracing::debug!(count = live_specs.len(), "successfully fetched live specs");
+
+    let collections = collect_specs(live_specs)?.collections;
+    let count = collections.len();
+    let written = write_collections(&args.output_dir, collections, args.no_overwrite)?;
+
+    println!(
+        "Wrote {} of {count} collection specifications under {}.",
+        written.len(),
+        args.output_dir.display()
+    );
+    Ok(())
+}
+
+/// Write each collection as its own `*.flow.yaml` catalog file within `dir`,
+/// returning the paths of files which were written. Files are named by the
+/// last component of the collection name, unless that component is shared by
+/// another collection, in which case the full name is used with `__` separators.
+/// If `no_overwrite`, then collections having an existing file are skipped.
+pub fn write_collections(
+    dir: &Path,
+    collections: BTreeMap<models::Collection, models::CollectionDef>,
+    no_overwrite: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("creating output directory {}", dir.display()))?;
+
+    let mut last_components: HashMap<&str, usize> = HashMap::new();
+    for name in collections.keys() {
+        *last_components.entry(last_component(name)).or_default() += 1;
+    }
+    let stems = collections
+        .keys()
+        .map(|name| match last_components[last_component(name)] {
+            1 => last_component(name).to_string(),
+            _ => name.replace('/', "__"),
+        })
+        .collect::<Vec<_>>();
+
+    let mut written = Vec::new();
+    for ((name, spec), stem) in collections.into_iter().zip(stems) {
+        let path = dir.join(format!("{stem}.flow.yaml"));
+
+        if no_overwrite && path.exists() {
+            tracing::info!(%name, path = %path.display(), "not overwriting existing file");
+            continue;
+        }
+        let catalog = models::Catalog {
+            collections: [(name, spec)].into_iter().collect(),
+            ..Default::default()
+        };
+        let content = serde_yaml::to_string(&catalog)?;
+
+        std::fs::write(&path, content).with_context(|| format!("writing {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn last_component(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_write_collections() {
+        // Live specs as they're returned by the control plane.
+        let rows: Vec<LiveSpecRow> = serde_json::from_value(json!([
+            {
+                "catalog_name": "acme/anvils",
+                "id": "0101010101010101",
+                "updated_at": "2023-06-01T12:00:00Z",
+                "spec_type": "collection",
+                "spec": {"schema": {"type": "object"}, "key": ["/id"]},
+            },
+            {
+                "catalog_name": "acme/east/orders",
+                "id": "0202020202020202",
+                "updated_at": "2023-06-01T12:00:00Z",
+                "spec_type": "collection",
+                "spec": {"schema": {"type": "object"}, "key": ["/order"]},
+            },
+            {
+                "catalog_name": "acme/west/orders",
+                "id": "0303030303030303",
+                "updated_at": "2023-06-01T12:00:00Z",
+                "spec_type": "collection",
+                "spec": {"schema": {"type": "object"}, "key": ["/order"]},
+            },
+        ]))
+        .unwrap();
+        let collections = collect_specs(rows).unwrap().collections;
+
+        let dir = tempfile::tempdir().unwrap();
+        let written = write_collections(dir.path(), collections.clone(), false).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                dir.path().join("anvils.flow.yaml"),
+                dir.path().join("acme__east__orders.flow.yaml"),
+                dir.path().join("acme__west__orders.flow.yaml"),
+            ]
+        );
+        let anvils: serde_json::Value =
+            serde_yaml::from_slice(&std::fs::read(&written[0]).unwrap()).unwrap();
+        assert_eq!(
+            anvils,
+            json!({
+                "collections": {
+                    "acme/anvils": {"schema": {"type": "object"}, "key": ["/id"]},
+                }
+            })
+        );
+
+        // Existing files are preserved with `no_overwrite`, and missing files are written.
+        std::fs::write(&written[0], "modified").unwrap();
+        std::fs::remove_file(&written[1]).unwrap();
+        let rewritten = write_collections(dir.path(), collections.clone(), true).unwrap();
+        assert_eq!(rewritten, &written[1..2]);
+        assert_eq!(std::fs::read_to_string(&written[0]).unwrap(), "modified");
+
+        // And are otherwise over-written.
+        write_collections(dir.path(), collections, false).unwrap();
+        assert_ne!(std::fs::read_to_string(&written[0]).unwrap(), "modified");
+    }
+}
@@ -1,6 +1,9 @@
+mod check_drift;
 mod delete;
+mod import;
 mod publish;
 mod pull_specs;
+mod status;
 mod test;
 
 use crate::{
@@ -42,6 +45,14 @@ pub enum Command {
     /// instead write the spec (and all associated endpoint config and schema resources)
     /// directly to the current directory.
     PullSpecs(pull_specs::PullSpecs),
+    /// Import collection specifications into a local directory.
+    ///
+    /// Writes each selected collection or derivation into its own
+    /// `<name>.flow.yaml` file within `--output-dir`, where `<name>` is the
+    /// last component of the collection name. If multiple collections share
+    /// that last component, their files are instead named by the full
+    /// collection name with `__` separators.
+    Import(import::Import),
     /// Publish catalog specifications
     ///
     /// Updates the running tasks, collections, and tests based on specifications in a
@@ -75,6 +86,23 @@ pub enum Command {
     /// Once in your draft, use `draft develop` and `draft author` to
     /// develop and author updates to the specification.
     Draft(Draft),
+    /// Show the health of collections and derivations.
+    ///
+    /// Lists selected collections along with their number of journals, the
+    /// hour they were last written, and their status. The lag of a derivation
+    /// is the difference between the write heads of its source journals and
+    /// the offsets checkpointed by its shards. Derivations are `lagging` if the
+    /// oldest source data they've yet to read was written more than
+    /// `--lag-threshold` ago, and `stopped` if they're disabled, have a failed
+    /// shard, or haven't read any of their written sources.
+    Status(status::Status),
+    /// Report journals which have drifted from their catalog specifications.
+    ///
+    /// Compares the live specification of each journal of the selected
+    /// collections against the partition template of its built collection,
+    /// and lists each field which differs. Journal names and labels are
+    /// expected to differ, and are not compared.
+    CheckDrift(check_drift::CheckDrift),
 }
 
 /// Common selection criteria based on the spec name.
@@ -260,10 +288,13 @@ impl Catalog {
             Command::List(list) => do_list(ctx, list).await,
             Command::Delete(del) => delete::do_delete(ctx, del).await,
             Command::PullSpecs(pull) => pull_specs::do_pull_specs(ctx, pull).await,
+            Command::Import(args) => import::do_import(ctx, args).await,
             Command::Publish(publish) => publish::do_publish(ctx, publish).await,
             Command::Test(source) => test::do_test(ctx, source).await,
             Command::History(history) => do_history(ctx, history).await,
             Command::Draft(draft) => do_draft(ctx, draft).await,
+            Command::Status(status) => status::do_status(ctx, status).await,
+            Command::CheckDrift(args) => check_drift::do_check_drift(ctx, args).await,
         }
     }
 }
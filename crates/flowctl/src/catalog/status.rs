@@ -0,0 +1,808 @@
+use super::{fetch_live_specs, List, NameSelector, SpecTypeSelector};
+use crate::{api_exec_paginated, collection::CollectionJournalSelector, dataplane};
+use anyhow::Context;
+use futures::stream::{FuturesUnordered, StreamExt};
+use itertools::Itertools;
+use journal_client::list::list_journals;
+use models::RawValue;
+use proto_gazette::{broker, consumer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+#[derive(Debug, clap::Args)]
+#[clap(rename_all = "kebab-case")]
+pub struct Status {
+    #[clap(flatten)]
+    pub name_selector: NameSelector,
+    /// Report a derivation as lagging if the oldest source data which it has
+    /// yet to read was written more than this duration ago.
+    #[clap(long, default_value = "5m")]
+    pub lag_threshold: humantime::Duration,
+}
+
+/// Health of a collection or derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    /// The collection is up to date with its sources (if any).
+    Ok,
+    /// The derivation is processing its sources, but trails them by more than the lag threshold.
+    Lagging,
+    /// The derivation is disabled or has a failed shard,
+    /// or hasn't read any documents though its sources were written.
+    Stopped,
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Health::Ok => "ok",
+            Health::Lagging => "lagging",
+            Health::Stopped => "stopped",
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusRow {
+    catalog_name: String,
+    derivation: bool,
+    journals: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_updated: Option<crate::Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lag_bytes: Option<i64>,
+    status: Health,
+}
+
+impl crate::output::CliOutput for StatusRow {
+    type TableAlt = ();
+    type CellValue = String;
+
+    fn table_headers(_alt: Self::TableAlt) -> Vec<&'static str> {
+        vec![
+            "Name",
+            "Type",
+            "Journals",
+            "Last Updated",
+            "Lag",
+            "Lag Bytes",
+            "Status",
+        ]
+    }
+
+    fn into_table_row(self, _alt: Self::TableAlt) -> Vec<Self::CellValue> {
+        vec![
+            self.catalog_name,
+            if self.derivation {
+                "derivation"
+            } else {
+                "collection"
+            }
+            .to_string(),
+            self.journals.to_string(),
+            self.last_updated
+                .map(|ts| ts.to_string())
+                .unwrap_or_default(),
+            self.lag.unwrap_or_default(),
+            self.lag_bytes
+                .map(|bytes| bytes.to_string())
+                .unwrap_or_default(),
+            self.status.to_string(),
+        ]
+    }
+}
+
+/// Lag of a derivation behind the write heads of its source journals.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Lag {
+    /// Bytes of source journals which are yet to be read.
+    pub bytes: i64,
+    /// Age of the oldest persisted source content which is yet to be read.
+    /// Unread content which isn't yet persisted has no known write time,
+    /// and contributes only to `bytes`.
+    pub age: time::Duration,
+}
+
+/// Classify the health of a derivation, given whether it's stopped (disabled,
+/// or having a failed shard), whether it has checkpointed reads of any of its
+/// sources, and its Lag behind their write heads.
+pub fn classify(
+    stopped: bool,
+    checkpointed: bool,
+    lag: Lag,
+    lag_threshold: time::Duration,
+) -> Health {
+    if stopped {
+        Health::Stopped
+    } else if !checkpointed && lag.bytes != 0 {
+        // Sources have been written but the derivation has never read them,
+        // so it's not making progress.
+        Health::Stopped
+    } else if lag.age > lag_threshold {
+        Health::Lagging
+    } else {
+        Health::Ok
+    }
+}
+
+pub async fn do_status(ctx: &mut crate::CliContext, status: &Status) -> anyhow::Result<()> {
+    let out = fetch_status(ctx, status).await?;
+    ctx.write_all(out, ())
+}
+
+async fn fetch_status(
+    ctx: &mut crate::CliContext,
+    status: &Status,
+) -> anyhow::Result<Vec<StatusRow>> {
+    #[derive(Deserialize)]
+    struct Row {
+        catalog_name: String,
+        reads_from: Option<Vec<String>>,
+        spec: Option<RawValue>,
+    }
+
+    let list = List {
+        name_selector: status.name_selector.clone(),
+        type_selector: SpecTypeSelector {
+            collections: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let rows = fetch_live_specs::<Row>(
+        ctx.controlplane_client().await?,
+        &list,
+        vec!["catalog_name", "reads_from", "spec"],
+    )
+    .await?;
+
+    let names = rows
+        .iter()
+        .map(|row| row.catalog_name.clone())
+        .collect::<Vec<_>>();
+    let last_writes = fetch_last_writes(ctx, &names).await?;
+
+    // We must be able to access all selected collections, as well as their
+    // sources which may not themselves be selected.
+    let prefixes = rows
+        .iter()
+        .flat_map(|row| std::iter::once(&row.catalog_name).chain(row.reads_from.iter().flatten()))
+        .cloned()
+        .sorted()
+        .dedup()
+        .collect();
+    let dataplane::DataPlaneAccess {
+        auth_token,
+        gateway_url,
+    } = dataplane::fetch_data_plane_access_token(ctx.controlplane_client().await?, prefixes)
+        .await?;
+
+    let pool = ctx.journal_client_pool();
+    let mut journal_client = pool
+        .client(
+            gateway_url.clone(),
+            auth_token.clone(),
+            dataplane::DEFAULT_MAX_MESSAGE_SIZE,
+        )
+        .await?;
+    let mut shard_client = pool.shard_client(gateway_url, auth_token).await?;
+
+    let lag_threshold =
+        time::Duration::try_from(*status.lag_threshold).context("converting --lag-threshold")?;
+    let now = OffsetDateTime::now_utc();
+    let mut out = Vec::with_capacity(rows.len());
+
+    for Row {
+        catalog_name,
+        reads_from,
+        spec,
+    } in rows
+    {
+        let spec: models::CollectionDef = match spec {
+            Some(spec) => serde_json::from_str(spec.get())
+                .with_context(|| format!("parsing specification of '{catalog_name}'"))?,
+            None => continue,
+        };
+        let journals = collection_journals(&mut journal_client, &catalog_name).await?;
+
+        let (health, lag) = match &spec.derive {
+            Some(derive) => {
+                let mut sources = Vec::new();
+                for source in reads_from.iter().flatten() {
+                    sources.extend(collection_journals(&mut journal_client, source).await?);
+                }
+                let progress = fetch_progress(&mut shard_client, &catalog_name).await?;
+                let lag = derivation_lag(&mut journal_client, &sources, &progress, now).await?;
+
+                let health = classify(
+                    derive.shards.disable || progress.stopped,
+                    progress.checkpoints.iter().any(|cp| !cp.is_empty()),
+                    lag,
+                    lag_threshold,
+                );
+                (health, Some(lag))
+            }
+            None => (Health::Ok, None),
+        };
+
+        out.push(StatusRow {
+            derivation: spec.derive.is_some(),
+            journals: journals.len(),
+            last_updated: last_writes
+                .get(&catalog_name)
+                .cloned()
+                .map(crate::Timestamp),
+            lag: lag.map(|lag| {
+                humantime::format_duration(std::time::Duration::from_secs(
+                    lag.age.whole_seconds() as u64
+                ))
+                .to_string()
+            }),
+            lag_bytes: lag.map(|lag| lag.bytes),
+            catalog_name,
+            status: health,
+        });
+    }
+
+    Ok(out)
+}
+
+/// Progress of the shards of a derivation.
+#[derive(Debug, Default)]
+struct Progress {
+    /// Offsets through which each shard has read, keyed on source journal.
+    checkpoints: Vec<BTreeMap<String, i64>>,
+    /// Whether any shard has stopped or has no assigned primary.
+    stopped: bool,
+}
+
+/// List the names of journals of the `collection`.
+async fn collection_journals(
+    client: &mut journal_client::Client,
+    collection: &str,
+) -> anyhow::Result<Vec<String>> {
+    let selector = CollectionJournalSelector {
+        collection: collection.to_string(),
+        partitions: None,
+    };
+    let journals = list_journals(client, &selector.build_label_selector())
+        .await
+        .with_context(|| format!("listing journals of '{collection}'"))?;
+
+    Ok(journals.into_iter().map(|spec| spec.name).collect())
+}
+
+/// Fetch the Progress of each shard of the `derivation`,
+/// from the read-through offsets of its most recent checkpoint.
+async fn fetch_progress(
+    client: &mut dataplane::ShardClient,
+    derivation: &str,
+) -> anyhow::Result<Progress> {
+    let selector = broker::LabelSelector {
+        include: Some(labels::build_set([(labels::TASK_NAME, derivation)])),
+        exclude: None,
+    };
+    let shards = client
+        .list(consumer::ListRequest {
+            selector: Some(selector),
+            ..Default::default()
+        })
+        .await
+        .with_context(|| format!("listing shards of '{derivation}'"))?
+        .into_inner()
+        .shards;
+
+    let mut progress = Progress::default();
+
+    for shard in shards {
+        let id = shard.spec.map(|spec| spec.id).unwrap_or_default();
+        let resp = client
+            .stat(consumer::StatRequest {
+                shard: id.clone(),
+                ..Default::default()
+            })
+            .await
+            .with_context(|| format!("fetching status of shard '{id}'"))?
+            .into_inner();
+
+        match resp.status() {
+            consumer::Status::Ok => (),
+            consumer::Status::ShardStopped
+            | consumer::Status::NoShardPrimary
+            | consumer::Status::ShardNotFound => {
+                tracing::debug!(shard = %id, status = ?resp.status(), "shard is not running");
+                progress.stopped = true;
+                continue;
+            }
+            status => anyhow::bail!("fetching status of shard '{id}': {}", status.as_str_name()),
+        }
+
+        // Checkpointed journals may have a suffix which identifies the reading transform,
+        // as in `acmeCo/source/pivot=00;derive/acmeCo/derivation/fromSource`.
+        let checkpoint = resp
+            .read_through
+            .into_iter()
+            .map(|(journal, offset)| match journal.split_once(';') {
+                Some((journal, _suffix)) => (journal.to_string(), offset),
+                None => (journal, offset),
+            })
+            .fold(BTreeMap::new(), |mut checkpoint, (journal, offset)| {
+                // If multiple transforms read a journal, the least offset is the lag.
+                let entry = checkpoint.entry(journal).or_insert(offset);
+                *entry = (*entry).min(offset);
+                checkpoint
+            });
+        progress.checkpoints.push(checkpoint);
+    }
+
+    Ok(progress)
+}
+
+/// Compute the Lag of a derivation behind the write heads of its `sources` journals,
+/// given the Progress of its shards. The Lag is the greatest lag of any shard.
+async fn derivation_lag(
+    client: &mut journal_client::Client,
+    sources: &[String],
+    progress: &Progress,
+    now: OffsetDateTime,
+) -> anyhow::Result<Lag> {
+    // A derivation without any checkpoints hasn't read its sources at all.
+    let never_read = [BTreeMap::new()];
+    let checkpoints = if progress.checkpoints.is_empty() {
+        &never_read[..]
+    } else {
+        &progress.checkpoints[..]
+    };
+
+    let mut lag = Lag::default();
+
+    for checkpoint in checkpoints {
+        let mut shard_bytes = 0;
+
+        for journal in sources {
+            let offset = checkpoint.get(journal).copied().unwrap_or_default();
+            let (write_head, mod_time) = read_metadata(client, journal, offset).await?;
+
+            if offset >= write_head {
+                continue;
+            }
+            shard_bytes += write_head - offset;
+
+            if let Some(mod_time) = mod_time {
+                lag.age = lag.age.max(now - mod_time);
+            }
+        }
+        lag.bytes = lag.bytes.max(shard_bytes);
+    }
+
+    Ok(lag)
+}
+
+/// Read metadata of `journal` at `offset`, returning its current write head
+/// and the modification time of the persisted fragment covering `offset`, if any.
+async fn read_metadata(
+    client: &mut journal_client::Client,
+    journal: &str,
+    offset: i64,
+) -> anyhow::Result<(i64, Option<OffsetDateTime>)> {
+    let mut stream = client
+        .read(broker::ReadRequest {
+            journal: journal.to_string(),
+            offset,
+            block: false,
+            metadata_only: true,
+            ..Default::default()
+        })
+        .await
+        .with_context(|| format!("reading metadata of journal '{journal}'"))?
+        .into_inner();
+
+    let resp = stream
+        .message()
+        .await?
+        .with_context(|| format!("missing read response of journal '{journal}'"))?;
+
+    // Fragments which are not yet persisted have no modification time.
+    let mod_time = match resp.fragment {
+        Some(fragment) if fragment.mod_time != 0 => {
+            Some(OffsetDateTime::from_unix_timestamp(fragment.mod_time)?)
+        }
+        _ => None,
+    };
+    Ok((resp.write_head, mod_time))
+}
+
+/// Fetch the most recent write to each of the given catalog names from their
+/// hourly stats. Writes are therefore resolved to the hour in which they occurred.
+async fn fetch_last_writes(
+    ctx: &mut crate::CliContext,
+    names: &[String],
+) -> anyhow::Result<BTreeMap<String, OffsetDateTime>> {
+    // Chunk requested names to avoid overflowing the postgREST URL length limit.
+    const BATCH_SIZE: usize = 25;
+
+    #[derive(Deserialize)]
+    struct StatsRow {
+        catalog_name: String,
+        ts: crate::Timestamp,
+        docs_written_to_me: i64,
+    }
+
+    let since = (OffsetDateTime::now_utc() - time::Duration::days(1))
+        .format(&time::format_description::well_known::Rfc3339)?;
+    let client = ctx.controlplane_client().await?;
+
+    let mut stream = names
+        .chunks(BATCH_SIZE)
+        .map(|batch| {
+            let builder = client
+                .from("catalog_stats")
+                .select("catalog_name,ts,docs_written_to_me")
+                .eq("grain", "hourly")
+                .gte("ts", &since)
+                .in_("catalog_name", batch);
+            async move { api_exec_paginated::<StatsRow>(builder).await }
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    let mut last_writes: BTreeMap<String, OffsetDateTime> = BTreeMap::new();
+    while let Some(result) = stream.next().await {
+        for row in result.context("fetching catalog stats")? {
+            if row.docs_written_to_me > 0 {
+                let entry = last_writes.entry(row.catalog_name).or_insert(row.ts.0);
+                *entry = (*entry).max(row.ts.0);
+            }
+        }
+    }
+    Ok(last_writes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::stream::BoxStream;
+    use proto_grpc::broker::journal_server::{Journal, JournalServer};
+    use proto_grpc::consumer::shard_server::{Shard, ShardServer};
+    use tonic::{Request, Response};
+    use warp::Filter;
+
+    #[test]
+    fn test_classify() {
+        let threshold = time::Duration::minutes(5);
+        let lag = |bytes, minutes| Lag {
+            bytes,
+            age: time::Duration::minutes(minutes),
+        };
+
+        // Caught up, or trailing by less than the threshold.
+        assert_eq!(classify(false, true, lag(0, 0), threshold), Health::Ok);
+        assert_eq!(classify(false, true, lag(1024, 3), threshold), Health::Ok);
+        // Trailing by more than the threshold.
+        assert_eq!(
+            classify(false, true, lag(1024, 17), threshold),
+            Health::Lagging
+        );
+        // Sources were written, but the derivation hasn't read anything.
+        assert_eq!(
+            classify(false, false, lag(1024, 0), threshold),
+            Health::Stopped
+        );
+        // No source content at all.
+        assert_eq!(classify(false, false, lag(0, 0), threshold), Health::Ok);
+        // Disabled derivations, or those having failed shards, are always stopped.
+        assert_eq!(classify(true, true, lag(0, 0), threshold), Health::Stopped);
+    }
+
+    // Source journal of the test fixture, which has 1000 bytes of content.
+    // Its first 500 bytes were persisted 17 minutes ago, and the remainder one minute ago.
+    const SOURCE_JOURNAL: &str = "acmeCo/src/pivot=00";
+    const SOURCE_WRITE_HEAD: i64 = 1000;
+
+    struct Broker {
+        now: i64,
+    }
+
+    #[tonic::async_trait]
+    impl Journal for Broker {
+        type ReadStream = BoxStream<'static, Result<broker::ReadResponse, tonic::Status>>;
+        type ReplicateStream = BoxStream<'static, Result<broker::ReplicateResponse, tonic::Status>>;
+
+        async fn list(
+            &self,
+            request: Request<broker::ListRequest>,
+        ) -> Result<Response<broker::ListResponse>, tonic::Status> {
+            let collection = label_value(request.into_inner().selector, labels::COLLECTION);
+
+            // Every collection has a single journal.
+            let journal = broker::list_response::Journal {
+                spec: Some(broker::JournalSpec {
+                    name: format!("{collection}/pivot=00"),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            Ok(Response::new(broker::ListResponse {
+                journals: vec![journal],
+                ..Default::default()
+            }))
+        }
+
+        async fn read(
+            &self,
+            request: Request<broker::ReadRequest>,
+        ) -> Result<Response<Self::ReadStream>, tonic::Status> {
+            let request = request.into_inner();
+            assert!(request.metadata_only && !request.block);
+            assert_eq!(request.journal, SOURCE_JOURNAL);
+
+            let fragment = if request.offset < 500 {
+                broker::Fragment {
+                    begin: 0,
+                    end: 500,
+                    mod_time: self.now - 17 * 60,
+                    ..Default::default()
+                }
+            } else {
+                broker::Fragment {
+                    begin: 500,
+                    end: SOURCE_WRITE_HEAD,
+                    mod_time: self.now - 60,
+                    ..Default::default()
+                }
+            };
+            let response = broker::ReadResponse {
+                offset: request.offset,
+                write_head: SOURCE_WRITE_HEAD,
+                fragment: Some(fragment),
+                ..Default::default()
+            };
+            Ok(Response::new(futures::stream::iter([Ok(response)]).boxed()))
+        }
+
+        async fn apply(
+            &self,
+            _request: Request<broker::ApplyRequest>,
+        ) -> Result<Response<broker::ApplyResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("apply"))
+        }
+        async fn append(
+            &self,
+            _request: Request<tonic::Streaming<broker::AppendRequest>>,
+        ) -> Result<Response<broker::AppendResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("append"))
+        }
+        async fn replicate(
+            &self,
+            _request: Request<tonic::Streaming<broker::ReplicateRequest>>,
+        ) -> Result<Response<Self::ReplicateStream>, tonic::Status> {
+            Err(tonic::Status::unimplemented("replicate"))
+        }
+        async fn list_fragments(
+            &self,
+            _request: Request<broker::FragmentsRequest>,
+        ) -> Result<Response<broker::FragmentsResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("list_fragments"))
+        }
+    }
+
+    struct Consumer;
+
+    #[tonic::async_trait]
+    impl Shard for Consumer {
+        async fn list(
+            &self,
+            request: Request<consumer::ListRequest>,
+        ) -> Result<Response<consumer::ListResponse>, tonic::Status> {
+            let task = label_value(request.into_inner().selector, labels::TASK_NAME);
+
+            // Derivations which were never started have no shards.
+            let shards = match task.as_str() {
+                "acmeCo/never" | "acmeCo/disabled" => Vec::new(),
+                task => vec![consumer::list_response::Shard {
+                    spec: Some(consumer::ShardSpec {
+                        id: format!("derivation/{task}/0"),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+            };
+            Ok(Response::new(consumer::ListResponse {
+                shards,
+                ..Default::default()
+            }))
+        }
+
+        async fn stat(
+            &self,
+            request: Request<consumer::StatRequest>,
+        ) -> Result<Response<consumer::StatResponse>, tonic::Status> {
+            let (status, offset) = match request.into_inner().shard.as_str() {
+                "derivation/acmeCo/ok/0" => (consumer::Status::Ok, 700),
+                "derivation/acmeCo/lagging/0" => (consumer::Status::Ok, 200),
+                "derivation/acmeCo/failed/0" => (consumer::Status::ShardStopped, 0),
+                shard => panic!("unexpected shard {shard}"),
+            };
+            let read_through = [(format!("{SOURCE_JOURNAL};derive/fromSrc"), offset)]
+                .into_iter()
+                .collect();
+
+            Ok(Response::new(consumer::StatResponse {
+                status: status as i32,
+                read_through,
+                ..Default::default()
+            }))
+        }
+
+        async fn apply(
+            &self,
+            _request: Request<consumer::ApplyRequest>,
+        ) -> Result<Response<consumer::ApplyResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("apply"))
+        }
+        async fn get_hints(
+            &self,
+            _request: Request<consumer::GetHintsRequest>,
+        ) -> Result<Response<consumer::GetHintsResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("get_hints"))
+        }
+        async fn unassign(
+            &self,
+            _request: Request<consumer::UnassignRequest>,
+        ) -> Result<Response<consumer::UnassignResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("unassign"))
+        }
+    }
+
+    fn label_value(selector: Option<broker::LabelSelector>, name: &str) -> String {
+        let labels = selector.and_then(|s| s.include).unwrap_or_default().labels;
+        let label = labels.into_iter().find(|label| label.name == name);
+        label.expect("selector includes label").value
+    }
+
+    // Serve a mock data-plane gateway on an ephemeral port, returning its URL.
+    async fn serve_gateway(now: i64) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let incoming = futures::stream::unfold(listener, |listener| async move {
+            let stream = listener.accept().await.map(|(stream, _)| stream);
+            Some((stream, listener))
+        });
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(JournalServer::new(Broker { now }))
+                .add_service(ShardServer::new(Consumer))
+                .serve_with_incoming(incoming),
+        );
+        gateway_url
+    }
+
+    // Serve a mock control-plane API on an ephemeral port, returning its URL.
+    fn serve_controlplane(gateway_url: String, now: OffsetDateTime) -> String {
+        let derivation = |disable: bool| {
+            serde_json::json!({
+                "schema": {"type": "object"},
+                "key": ["/id"],
+                "derive": {
+                    "using": {"sqlite": {}},
+                    "transforms": [],
+                    "shards": {"disable": disable},
+                },
+            })
+        };
+        let row = |name: &str, spec: serde_json::Value| {
+            let reads_from = spec.get("derive").map(|_| vec!["acmeCo/src"]);
+            serde_json::json!({"catalog_name": name, "reads_from": reads_from, "spec": spec})
+        };
+        let live_specs = serde_json::json!([
+            row(
+                "acmeCo/src",
+                serde_json::json!({"schema": {"type": "object"}, "key": ["/id"]})
+            ),
+            row("acmeCo/ok", derivation(false)),
+            row("acmeCo/lagging", derivation(false)),
+            row("acmeCo/never", derivation(false)),
+            row("acmeCo/failed", derivation(false)),
+            row("acmeCo/disabled", derivation(true)),
+        ]);
+        let hour = now.replace_minute(0).unwrap().replace_second(0).unwrap();
+        let stats = serde_json::json!([{
+            "catalog_name": "acmeCo/src",
+            "ts": hour.format(&time::format_description::well_known::Rfc3339).unwrap(),
+            "docs_written_to_me": 42,
+        }]);
+        let access = serde_json::json!([{"token": "a-token", "gateway_url": gateway_url}]);
+
+        let api = warp::path!("live_specs_ext")
+            .map(move || warp::reply::json(&live_specs))
+            .or(warp::path!("catalog_stats").map(move || warp::reply::json(&stats)))
+            .or(warp::post()
+                .and(warp::path!("rpc" / "gateway_auth_token"))
+                .map(move || warp::reply::json(&access)));
+
+        let (addr, server) = warp::serve(api).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let now = OffsetDateTime::now_utc();
+        let gateway_url = serve_gateway(now.unix_timestamp()).await;
+        let api_url = serve_controlplane(gateway_url, now);
+
+        let mut ctx = crate::CliContext {
+            config: Default::default(),
+            output: Default::default(),
+            controlplane_client: Some(crate::controlplane::Client::new(postgrest::Postgrest::new(
+                api_url,
+            ))),
+            journal_client_pool: Default::default(),
+        };
+        let status = Status {
+            name_selector: Default::default(),
+            lag_threshold: std::time::Duration::from_secs(300).into(),
+        };
+        let rows = fetch_status(&mut ctx, &status).await.unwrap();
+
+        // Lags are compared by their leading component, as the mock's
+        // clock may have ticked over a second since the fixture was built.
+        let rows = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.catalog_name.as_str(),
+                    row.last_updated.is_some(),
+                    row.status,
+                    row.lag_bytes,
+                    row.lag.as_deref().and_then(|lag| lag.split(' ').next()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            rows,
+            vec![
+                ("acmeCo/src", true, Health::Ok, None, None),
+                // Trails its source by 300 bytes written a minute ago.
+                ("acmeCo/ok", false, Health::Ok, Some(300), Some("1m")),
+                // Trails its source by 800 bytes, the oldest written 17 minutes ago.
+                (
+                    "acmeCo/lagging",
+                    false,
+                    Health::Lagging,
+                    Some(800),
+                    Some("17m")
+                ),
+                // Has never read its source.
+                (
+                    "acmeCo/never",
+                    false,
+                    Health::Stopped,
+                    Some(1000),
+                    Some("17m")
+                ),
+                // Has a failed shard.
+                (
+                    "acmeCo/failed",
+                    false,
+                    Health::Stopped,
+                    Some(1000),
+                    Some("17m")
+                ),
+                (
+                    "acmeCo/disabled",
+                    false,
+                    Health::Stopped,
+                    Some(1000),
+                    Some("17m")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use super::{list_persisted_fragments, CollectionJournalSelector};
+use anyhow::Context;
+use futures::{AsyncReadExt, StreamExt, TryStreamExt};
+use journal_client::read::uncommitted::fragment::FragmentReader;
+use proto_gazette::broker;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyChecksumsArgs {
+    #[clap(flatten)]
+    pub selector: CollectionJournalSelector,
+
+    /// Only verify fragments which were written within the provided duration from the present.
+    #[clap(long)]
+    pub since: Option<humantime::Duration>,
+
+    /// Number of fragments to download and verify concurrently.
+    #[clap(long, default_value = "4")]
+    pub parallelism: usize,
+}
+
+/// A fragment having content which doesn't match its SHA1 sum.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mismatch {
+    journal: String,
+    begin: i64,
+    end: i64,
+    store: String,
+    expected: String,
+    actual: String,
+}
+
+impl crate::output::CliOutput for Mismatch {
+    type TableAlt = ();
+    type CellValue = String;
+
+    fn table_headers(_alt: Self::TableAlt) -> Vec<&'static str> {
+        vec![
+            "Journal",
+            "Begin Offset",
+            "End Offset",
+            "Store",
+            "Expected SHA1",
+            "Actual SHA1",
+        ]
+    }
+
+    fn into_table_row(self, _alt: Self::TableAlt) -> Vec<Self::CellValue> {
+        vec![
+            self.journal,
+            self.begin.to_string(),
+            self.end.to_string(),
+            self.store,
+            self.expected,
+            self.actual,
+        ]
+    }
+}
+
+pub async fn do_verify_checksums(
+    ctx: &mut crate::CliContext,
+    args: &VerifyChecksumsArgs,
+) -> anyhow::Result<()> {
+    // Signed URLs must remain valid for the duration of all fragment downloads.
+    let signature_ttl = std::time::Duration::from_secs(3600);
+    let fragments =
+        list_persisted_fragments(ctx, &args.selector, args.since, signature_ttl).await?;
+    tracing::debug!(count = fragments.len(), "verifying fragment checksums");
+
+    let mismatches = verify_fragments(fragments, args.parallelism).await?;
+    ctx.write_all(mismatches, ())
+}
+
+/// Download and verify the content of each fragment against its SHA1 sum,
+/// with at most `parallelism` concurrent downloads. Returns the fragments
+/// having content which doesn't match, ordered on journal and begin offset.
+pub async fn verify_fragments(
+    fragments: Vec<broker::fragments_response::Fragment>,
+    parallelism: usize,
+) -> anyhow::Result<Vec<Mismatch>> {
+    let mut mismatches: Vec<Mismatch> = futures::stream::iter(fragments)
+        .map(verify_fragment)
+        .buffer_unordered(parallelism.max(1))
+        .try_filter_map(|mismatch| async move { Ok(mismatch) })
+        .try_collect()
+        .await?;
+
+    mismatches.sort_by(|l, r| (&l.journal, l.begin).cmp(&(&r.journal, r.begin)));
+    Ok(mismatches)
+}
+
+async fn verify_fragment(
+    fragment: broker::fragments_response::Fragment,
+) -> anyhow::Result<Option<Mismatch>> {
+    let broker::fragments_response::Fragment { spec, signed_url } = fragment;
+    let spec = spec.context("missing spec of FragmentsResponse")?;
+
+    let journal = spec.journal.clone();
+    let (begin, end) = (spec.begin, spec.end);
+    let store = spec.backing_store.clone();
+    let expected = sha1sum_hex(spec.sum.as_ref().unwrap_or(&Default::default()));
+
+    // FragmentReader decompresses the fragment file, yielding its original content.
+    let mut reader = FragmentReader::new(signed_url, spec);
+    let mut hasher = Sha1::new();
+    let mut buf = vec![0; 1 << 16];
+
+    loop {
+        let n = reader
+            .read(&mut buf)
+            .await
+            .with_context(|| format!("reading fragment {journal}:{begin}-{end}"))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let actual = hex::encode(hasher.finalize());
+
+    if actual == expected {
+        return Ok(None);
+    }
+    Ok(Some(Mismatch {
+        journal,
+        begin,
+        end,
+        store,
+        expected,
+        actual,
+    }))
+}
+
+// Format a SHA1 sum as hex, matching the encoding used by Gazette fragment names.
+pub(super) fn sha1sum_hex(sum: &broker::Sha1Sum) -> String {
+    format!("{:016x}{:016x}{:08x}", sum.part1, sum.part2, sum.part3)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use warp::Filter;
+
+    fn sha1sum(content: &[u8]) -> broker::Sha1Sum {
+        let digest = Sha1::digest(content);
+        broker::Sha1Sum {
+            part1: u64::from_be_bytes(digest[0..8].try_into().unwrap()),
+            part2: u64::from_be_bytes(digest[8..16].try_into().unwrap()),
+            part3: u32::from_be_bytes(digest[16..20].try_into().unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_fragments() {
+        // Serve fragment files, as a cloud storage endpoint would.
+        // The content of "corrupt" doesn't match the content it was written with.
+        let files = warp::path!("bucket" / String).map(|name: String| match name.as_str() {
+            "good" => "hello, world!\n",
+            "corrupt" => "hello, wyrld!\n",
+            _ => "",
+        });
+        let (addr, server) = warp::serve(files).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let fragment = |name: &str, begin: i64| broker::fragments_response::Fragment {
+            spec: Some(broker::Fragment {
+                journal: "acme/anvils/pivot=00".to_string(),
+                begin,
+                end: begin + 14,
+                sum: Some(sha1sum(b"hello, world!\n")),
+                compression_codec: broker::CompressionCodec::None as i32,
+                backing_store: "s3://bucket/".to_string(),
+                ..Default::default()
+            }),
+            signed_url: format!("http://{addr}/bucket/{name}"),
+        };
+
+        let mismatches = verify_fragments(
+            vec![
+                fragment("good", 0),
+                fragment("corrupt", 14),
+                fragment("good", 28),
+            ],
+            2,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            mismatches,
+            vec![Mismatch {
+                journal: "acme/anvils/pivot=00".to_string(),
+                begin: 14,
+                end: 28,
+                store: "s3://bucket/".to_string(),
+                expected: hex::encode(Sha1::digest(b"hello, world!\n")),
+                actual: hex::encode(Sha1::digest(b"hello, wyrld!\n")),
+            }]
+        );
+    }
+}
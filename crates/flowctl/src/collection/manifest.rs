@@ -0,0 +1,414 @@
+use super::{checksums::sha1sum_hex, list_persisted_fragments, CollectionJournalSelector};
+use anyhow::Context;
+use futures::{StreamExt, TryStreamExt};
+use proto_gazette::broker;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio::io::AsyncWriteExt;
+
+#[derive(clap::Args, Debug)]
+pub struct ExportManifestArgs {
+    #[clap(flatten)]
+    pub selector: CollectionJournalSelector,
+
+    /// Format of the output manifest.
+    #[clap(long, value_enum, default_value = "ndjson")]
+    pub format: ManifestFormat,
+
+    /// Only include fragments which were written within the provided duration from the present.
+    #[clap(long)]
+    pub since: Option<humantime::Duration>,
+
+    /// Number of fragment file sizes to fetch from cloud storage concurrently.
+    #[clap(long, default_value = "16")]
+    pub parallelism: usize,
+}
+
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum ManifestFormat {
+    /// A JSON object for each fragment, separated by newlines
+    Ndjson,
+    /// Comma-separated values, with a header row and a row for each fragment
+    Csv,
+    /// Delta Lake `add` actions for each fragment, separated by newlines
+    DeltaLakeManifest,
+}
+
+/// A persisted fragment and the size of its file within its backing store.
+#[derive(Debug)]
+pub struct StoredFragment {
+    pub spec: broker::Fragment,
+    pub stored_size: i64,
+}
+
+/// An entry of the manifest, describing a single persisted fragment.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    journal: String,
+    /// Full path of the fragment file within its backing store.
+    path: String,
+    begin: i64,
+    end: i64,
+    /// Size in bytes of the fragment file within its backing store.
+    /// This differs from `end - begin` if the file is compressed.
+    stored_size: i64,
+    /// Modification time of the fragment, in seconds since the Unix epoch.
+    mod_time: i64,
+}
+
+pub async fn do_export_manifest(
+    ctx: &mut crate::CliContext,
+    args: &ExportManifestArgs,
+) -> anyhow::Result<()> {
+    // Signed URLs are used to fetch the size of each fragment file,
+    // and must remain valid until all sizes are fetched.
+    let signature_ttl = std::time::Duration::from_secs(3600);
+    let fragments =
+        list_persisted_fragments(ctx, &args.selector, args.since, signature_ttl).await?;
+    tracing::debug!(count = fragments.len(), "exporting manifest of fragments");
+
+    let fragments = fetch_stored_sizes(fragments, args.parallelism).await?;
+
+    let mut buf = Vec::new();
+    write_manifest(&args.selector.collection, fragments, args.format, &mut buf)?;
+    tokio::io::stdout().write_all(&buf).await?;
+
+    Ok(())
+}
+
+/// Fetch the size of each fragment file from its backing store, using its
+/// signed URL, with at most `parallelism` concurrent requests. Fragment
+/// metadata records only the offsets of its uncompressed content, and not
+/// the size of its (possibly compressed) file.
+pub async fn fetch_stored_sizes(
+    fragments: Vec<broker::fragments_response::Fragment>,
+    parallelism: usize,
+) -> anyhow::Result<Vec<StoredFragment>> {
+    let client = reqwest::Client::new();
+
+    futures::stream::iter(fragments)
+        .map(|fragment| fetch_stored_size(&client, fragment))
+        .buffered(parallelism.max(1))
+        .try_collect()
+        .await
+}
+
+async fn fetch_stored_size(
+    client: &reqwest::Client,
+    fragment: broker::fragments_response::Fragment,
+) -> anyhow::Result<StoredFragment> {
+    let broker::fragments_response::Fragment { spec, signed_url } = fragment;
+    let spec = spec.context("missing spec of FragmentsResponse")?;
+
+    // Signed URLs authorize only GET requests, so rather than a HEAD request
+    // we GET the first byte, and take the file size from its Content-Range.
+    let response = client
+        .get(&signed_url)
+        .header(reqwest::header::RANGE, "bytes=0-0")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| {
+            format!(
+                "fetching size of fragment {}:{}-{}",
+                spec.journal, spec.begin, spec.end
+            )
+        })?;
+
+    let stored_size = match response.headers().get(reqwest::header::CONTENT_RANGE) {
+        // Content-Range is formatted as `bytes 0-0/{size}`.
+        Some(range) => range
+            .to_str()
+            .ok()
+            .and_then(|range| range.rsplit_once('/'))
+            .and_then(|(_, size)| size.parse().ok()),
+        // The store ignored our range and is returning the whole file.
+        None => response.content_length().map(|size| size as i64),
+    }
+    .with_context(|| {
+        format!(
+            "missing size of fragment {}:{}-{}",
+            spec.journal, spec.begin, spec.end
+        )
+    })?;
+
+    Ok(StoredFragment { spec, stored_size })
+}
+
+/// Write a manifest of the persisted `fragments` of `collection` into `buf`.
+/// Fragments which are not yet persisted to a backing store are omitted.
+pub fn write_manifest(
+    collection: &str,
+    fragments: Vec<StoredFragment>,
+    format: ManifestFormat,
+    buf: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+    let entries = fragments
+        .iter()
+        .filter(|fragment| !fragment.spec.backing_store.is_empty())
+        .map(|StoredFragment { spec, stored_size }| ManifestEntry {
+            journal: spec.journal.clone(),
+            path: fragment_path(spec),
+            begin: spec.begin,
+            end: spec.end,
+            stored_size: *stored_size,
+            mod_time: spec.mod_time,
+        });
+
+    match format {
+        ManifestFormat::Ndjson => {
+            for entry in entries {
+                serde_json::to_writer(&mut *buf, &entry)?;
+                buf.push(b'\n');
+            }
+        }
+        ManifestFormat::Csv => {
+            let mut w = csv::Writer::from_writer(buf);
+            for entry in entries {
+                w.serialize(entry)?;
+            }
+            w.flush()?;
+        }
+        ManifestFormat::DeltaLakeManifest => {
+            for entry in entries {
+                let action = serde_json::json!({
+                    "add": {
+                        "path": entry.path,
+                        "size": entry.stored_size,
+                        "modificationTime": entry.mod_time * 1000,
+                        "partitionValues": partition_values(collection, &entry.journal),
+                        "dataChange": true,
+                    }
+                });
+                serde_json::to_writer(&mut *buf, &action)?;
+                buf.push(b'\n');
+            }
+        }
+    }
+    Ok(())
+}
+
+// Full path of a fragment file within its backing store.
+// *** This MUST match the Go-side behavior of Fragment.ContentPath! ***
+fn fragment_path(spec: &broker::Fragment) -> String {
+    let extension = match spec.compression_codec() {
+        broker::CompressionCodec::Invalid | broker::CompressionCodec::None => ".raw",
+        broker::CompressionCodec::Gzip => ".gz",
+        broker::CompressionCodec::Zstandard => ".zst",
+        broker::CompressionCodec::Snappy => ".sz",
+        broker::CompressionCodec::GzipOffloadDecompression => ".gzod",
+    };
+    let name = format!(
+        "{:016x}-{:016x}-{}{extension}",
+        spec.begin,
+        spec.end,
+        sha1sum_hex(spec.sum.as_ref().unwrap_or(&Default::default())),
+    );
+
+    if spec.path_postfix.is_empty() {
+        format!("{}{}/{name}", spec.backing_store, spec.journal)
+    } else {
+        format!(
+            "{}{}/{}/{name}",
+            spec.backing_store, spec.journal, spec.path_postfix
+        )
+    }
+}
+
+// Partition values of a journal of the collection, which are encoded into the
+// journal name as `{collection}/{field}={value}/.../pivot={key}`. String values
+// are percent-encoded, and other values are their literal JSON prefixed with `%_`.
+fn partition_values(collection: &str, journal: &str) -> BTreeMap<String, String> {
+    let Some(suffix) = journal
+        .strip_prefix(collection)
+        .and_then(|s| s.strip_prefix('/'))
+    else {
+        return BTreeMap::new();
+    };
+
+    suffix
+        .split('/')
+        .flat_map(|segment| url::form_urlencoded::parse(segment.as_bytes()))
+        .filter(|(field, _)| field != "pivot")
+        .map(|(field, value)| {
+            let value = match value.strip_prefix("%_") {
+                Some(literal) => literal.to_string(),
+                None => value.into_owned(),
+            };
+            (field.into_owned(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::{json, Value};
+    use warp::Filter;
+
+    fn fragment_spec(journal: &str, begin: i64, store: &str, postfix: &str) -> broker::Fragment {
+        broker::Fragment {
+            journal: journal.to_string(),
+            begin,
+            end: begin + 1024,
+            sum: Some(broker::Sha1Sum {
+                part1: 0x0102030405060708,
+                part2: 0x090a0b0c0d0e0f10,
+                part3: 0x11121314,
+            }),
+            compression_codec: broker::CompressionCodec::Gzip as i32,
+            backing_store: store.to_string(),
+            mod_time: 1700000000 + begin,
+            path_postfix: postfix.to_string(),
+        }
+    }
+
+    fn fragments() -> Vec<StoredFragment> {
+        // Fragment files are compressed, and smaller than their content.
+        let fragment = |journal: &str, begin: i64, store: &str, postfix: &str| StoredFragment {
+            spec: fragment_spec(journal, begin, store, postfix),
+            stored_size: 300 + begin,
+        };
+
+        vec![
+            fragment(
+                "acme/events/region=eu/year=%_2023/pivot=00",
+                0,
+                "s3://bucket/",
+                "",
+            ),
+            fragment(
+                "acme/events/region=north%20america/year=%_2024/pivot=00",
+                1024,
+                "gs://bucket/prefix/",
+                "utc_date=2024-01-01",
+            ),
+            // Not yet persisted, and omitted from the manifest.
+            fragment("acme/events/region=eu/year=%_2023/pivot=00", 2048, "", ""),
+        ]
+    }
+
+    fn export(format: ManifestFormat) -> String {
+        let mut buf = Vec::new();
+        write_manifest("acme/events", fragments(), format, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_delta_lake_manifest() {
+        let actions: Vec<Value> = export(ManifestFormat::DeltaLakeManifest)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(
+            actions,
+            vec![
+                json!({"add": {
+                    "path": "s3://bucket/acme/events/region=eu/year=%_2023/pivot=00/0000000000000000-0000000000000400-0102030405060708090a0b0c0d0e0f1011121314.gz",
+                    "size": 300,
+                    "modificationTime": 1700000000000_i64,
+                    "partitionValues": {"region": "eu", "year": "2023"},
+                    "dataChange": true,
+                }}),
+                json!({"add": {
+                    "path": "gs://bucket/prefix/acme/events/region=north%20america/year=%_2024/pivot=00/utc_date=2024-01-01/0000000000000400-0000000000000800-0102030405060708090a0b0c0d0e0f1011121314.gz",
+                    "size": 1324,
+                    "modificationTime": 1700001024000_i64,
+                    "partitionValues": {"region": "north america", "year": "2024"},
+                    "dataChange": true,
+                }}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ndjson_and_csv_manifests() {
+        let ndjson = export(ManifestFormat::Ndjson);
+        let first: Value = serde_json::from_str(ndjson.lines().next().unwrap()).unwrap();
+        assert_eq!(
+            first,
+            json!({
+                "journal": "acme/events/region=eu/year=%_2023/pivot=00",
+                "path": "s3://bucket/acme/events/region=eu/year=%_2023/pivot=00/0000000000000000-0000000000000400-0102030405060708090a0b0c0d0e0f1011121314.gz",
+                "begin": 0,
+                "end": 1024,
+                "storedSize": 300,
+                "modTime": 1700000000,
+            })
+        );
+        assert_eq!(ndjson.lines().count(), 2);
+
+        let csv = export(ManifestFormat::Csv);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("journal,path,begin,end,storedSize,modTime")
+        );
+        assert_eq!(lines.count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stored_sizes() {
+        // Serve fragment files, as a cloud storage endpoint would. The "ranged"
+        // file honors range requests, while "whole" returns the entire file.
+        let files = warp::path!("bucket" / String)
+            .and(warp::header::optional::<String>("range"))
+            .map(|name: String, range: Option<String>| {
+                let body = "x".repeat(300);
+                match (name.as_str(), range.as_deref()) {
+                    ("ranged", Some("bytes=0-0")) => warp::http::Response::builder()
+                        .status(206)
+                        .header("content-range", "bytes 0-0/300")
+                        .body(body[..1].to_string()),
+                    ("whole", _) => warp::http::Response::builder().body(body),
+                    _ => warp::http::Response::builder()
+                        .status(404)
+                        .body(String::new()),
+                }
+            });
+        let (addr, server) = warp::serve(files).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let fragment = |name: &str, begin: i64| broker::fragments_response::Fragment {
+            spec: Some(fragment_spec(
+                "acme/events/pivot=00",
+                begin,
+                "s3://bucket/",
+                "",
+            )),
+            signed_url: format!("http://{addr}/bucket/{name}"),
+        };
+
+        let sizes = fetch_stored_sizes(vec![fragment("ranged", 0), fragment("whole", 1024)], 2)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|fragment| (fragment.spec.begin, fragment.stored_size))
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec![(0, 300), (1024, 300)]);
+
+        // A file which can't be fetched is an error.
+        let err = fetch_stored_sizes(vec![fragment("missing", 0)], 2)
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").starts_with("fetching size of fragment acme/events/pivot=00:0-1024"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_partition_values() {
+        assert_eq!(
+            partition_values("acme/events", "acme/events/flag=%_true/name=a%2Fb/pivot=00"),
+            [("flag", "true"), ("name", "a/b")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        assert!(partition_values("acme/events", "acme/events/pivot=00").is_empty());
+        assert!(partition_values("acme/other", "acme/events/pivot=00").is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+mod checksums;
+mod manifest;
 pub mod read;
 
 use crate::Timestamp;
@@ -6,7 +8,7 @@ use journal_client::{fragments, list};
 use proto_gazette::broker;
 use time::OffsetDateTime;
 
-use crate::dataplane::journal_client_for;
+use crate::dataplane::{journal_client_for, DEFAULT_MAX_MESSAGE_SIZE};
 use crate::output::{to_table_row, CliOutput, JsonCell};
 
 use self::read::ReadArgs;
@@ -52,6 +54,19 @@ pub enum Command {
     ListJournals(CollectionJournalSelector),
     /// List the journal fragments of a flow collection
     ListFragments(ListFragmentsArgs),
+    /// Verify the content of persisted journal fragments against their checksums
+    ///
+    /// Downloads each persisted fragment of the collection's journals, and
+    /// compares the SHA1 sum of its content against the sum recorded in the
+    /// fragment's metadata. Fragments having content which doesn't match are listed.
+    VerifyChecksums(checksums::VerifyChecksumsArgs),
+    /// Export a manifest of the persisted journal fragments of a collection
+    ///
+    /// The manifest lists the full path of each fragment file within its
+    /// backing store, along with its offsets, stored file size, and modification time.
+    /// It's intended for use by bulk analytics jobs which read fragment files
+    /// directly from cloud storage.
+    ExportManifest(manifest::ExportManifestArgs),
 }
 
 impl Collections {
@@ -60,6 +75,8 @@ impl Collections {
             Command::Read(args) => do_read(ctx, args).await,
             Command::ListJournals(selector) => do_list_journals(ctx, selector).await,
             Command::ListFragments(args) => do_list_fragments(ctx, args).await,
+            Command::VerifyChecksums(args) => checksums::do_verify_checksums(ctx, args).await,
+            Command::ExportManifest(args) => manifest::do_export_manifest(ctx, args).await,
         }
     }
 }
@@ -112,6 +129,30 @@ pub struct ListFragmentsArgs {
     /// For example, `--since 10m` will only output fragments that have been written within the last 10 minutes.
     #[clap(long)]
     pub since: Option<humantime::Duration>,
+
+    /// Only include fragments of journals having a name which matches the given glob pattern.
+    /// The pattern is matched against the full journal name, including its partition labels.
+    /// For example, `--journal-filter '*/year=2023/*'`.
+    #[clap(long, value_parser(parse_journal_filter))]
+    pub journal_filter: Option<glob::Pattern>,
+}
+
+fn parse_journal_filter(arg: &str) -> Result<glob::Pattern, anyhow::Error> {
+    glob::Pattern::new(arg).context("parsing `--journal-filter` argument value")
+}
+
+// Filter journals to those having a name matching the |filter|, if present.
+fn filter_journals(
+    journals: Vec<broker::JournalSpec>,
+    filter: Option<&glob::Pattern>,
+) -> Vec<broker::JournalSpec> {
+    match filter {
+        Some(pattern) => journals
+            .into_iter()
+            .filter(|journal| pattern.matches(&journal.name))
+            .collect(),
+        None => journals,
+    }
 }
 
 impl CliOutput for broker::fragments_response::Fragment {
@@ -173,11 +214,15 @@ async fn do_list_fragments(
 ) -> Result<(), anyhow::Error> {
     let mut client = journal_client_for(
         ctx.controlplane_client().await?,
+        ctx.journal_client_pool(),
         vec![args.selector.collection.clone()],
+        DEFAULT_MAX_MESSAGE_SIZE,
     )
     .await?;
 
     let journals = list::list_journals(&mut client, &args.selector.build_label_selector()).await?;
+    let journals = filter_journals(journals, args.journal_filter.as_ref());
+    tracing::debug!(count = journals.len(), "listing fragments of journals");
 
     let start_time = if let Some(since) = args.since {
         let timepoint = OffsetDateTime::now_utc() - *since;
@@ -210,13 +255,62 @@ async fn do_list_fragments(
     ctx.write_all(fragments, args.signature_ttl.is_some())
 }
 
+/// List the fragments of the journals of `selector` which have been persisted
+/// to a fragment store, having signed URLs which remain valid for `signature_ttl`.
+/// If `since` is set, only fragments modified within that duration are listed.
+async fn list_persisted_fragments(
+    ctx: &mut crate::CliContext,
+    selector: &CollectionJournalSelector,
+    since: Option<humantime::Duration>,
+    signature_ttl: std::time::Duration,
+) -> anyhow::Result<Vec<broker::fragments_response::Fragment>> {
+    let mut client = journal_client_for(
+        ctx.controlplane_client().await?,
+        ctx.journal_client_pool(),
+        vec![selector.collection.clone()],
+        DEFAULT_MAX_MESSAGE_SIZE,
+    )
+    .await?;
+
+    let journals = list::list_journals(&mut client, &selector.build_label_selector()).await?;
+
+    let begin_mod_time = match since {
+        Some(since) => (OffsetDateTime::now_utc() - *since).unix_timestamp(),
+        None => 0,
+    };
+
+    let mut fragments = Vec::new();
+    for journal in journals {
+        let req = broker::FragmentsRequest {
+            journal: journal.name.clone(),
+            begin_mod_time,
+            page_limit: 500,
+            signature_ttl: Some(signature_ttl.into()),
+            ..Default::default()
+        };
+        let mut fragment_iter = fragments::FragmentIter::new(client.clone(), req);
+
+        while let Some(fragment) = fragment_iter.next().await {
+            let fragment = fragment?;
+
+            // Fragments which aren't yet persisted have no signed URL.
+            if !fragment.signed_url.is_empty() {
+                fragments.push(fragment);
+            }
+        }
+    }
+    Ok(fragments)
+}
+
 async fn do_list_journals(
     ctx: &mut crate::CliContext,
     args: &CollectionJournalSelector,
 ) -> Result<(), anyhow::Error> {
     let mut client = journal_client_for(
         ctx.controlplane_client().await?,
+        ctx.journal_client_pool(),
         vec![args.collection.clone()],
+        DEFAULT_MAX_MESSAGE_SIZE,
     )
     .await?;
 
@@ -224,3 +318,42 @@ async fn do_list_journals(
 
     ctx.write_all(journals, ())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filter_journals() {
+        let journals: Vec<broker::JournalSpec> = [2021, 2022, 2023]
+            .into_iter()
+            .flat_map(|year| {
+                ["eu", "us", "apac"]
+                    .into_iter()
+                    .map(move |region| broker::JournalSpec {
+                        name: format!("acme/events/region={region}/year={year}/pivot=00"),
+                        ..Default::default()
+                    })
+            })
+            .chain(std::iter::once(broker::JournalSpec {
+                name: "acme/events/region=eu/year=20234/pivot=00".to_string(),
+                ..Default::default()
+            }))
+            .collect();
+        assert_eq!(journals.len(), 10);
+
+        let pattern = parse_journal_filter("*/year=2023/*").unwrap();
+        let filtered = filter_journals(journals.clone(), Some(&pattern));
+
+        assert_eq!(
+            filtered.iter().map(|j| j.name.as_str()).collect::<Vec<_>>(),
+            vec![
+                "acme/events/region=eu/year=2023/pivot=00",
+                "acme/events/region=us/year=2023/pivot=00",
+                "acme/events/region=apac/year=2023/pivot=00",
+            ]
+        );
+        assert_eq!(filter_journals(journals, None).len(), 10);
+        assert!(parse_journal_filter("acme/[unclosed").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use serde_json::Value;
+
+/// Format of documents which are output by `collections read`.
+#[derive(clap::ValueEnum, Debug, Default, Copy, Clone, PartialEq)]
+pub enum Format {
+    /// Compact JSON documents, separated by newlines
+    #[default]
+    Ndjson,
+    /// A single JSON array of all documents
+    JsonArray,
+    /// Pretty-printed JSON documents, separated by newlines
+    Pretty,
+    /// Comma-separated values, having a column for each top-level property
+    /// of the first document. Acknowledgement documents are omitted
+    Csv,
+}
+
+/// Parse a CSV delimiter, which must be a single ASCII character.
+pub fn parse_csv_delimiter(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [b] if b.is_ascii() => Ok(*b),
+        _ => Err(format!("{s:?} is not a single ASCII character")),
+    }
+}
+
+/// Encoder of output documents in a Format. Documents are encoded one at a
+/// time, and only the current document is buffered.
+pub struct Encoder {
+    format: Format,
+    count: usize,
+    // CSV output options, and columns determined from the first document.
+    csv_header: bool,
+    csv_delimiter: u8,
+    csv_columns: Option<Vec<String>>,
+}
+
+impl Encoder {
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            count: 0,
+            csv_header: true,
+            csv_delimiter: b',',
+            csv_columns: None,
+        }
+    }
+
+    /// Set whether a header row is written, and the delimiter of CSV output.
+    /// These have no effect unless the Format is Csv.
+    pub fn with_csv_options(self, header: bool, delimiter: u8) -> Self {
+        Self {
+            csv_header: header,
+            csv_delimiter: delimiter,
+            ..self
+        }
+    }
+
+    /// Encode the next document into `buf`.
+    pub fn encode(&mut self, doc: &Value, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        match self.format {
+            Format::Ndjson => {
+                serde_json::to_writer(&mut *buf, doc)?;
+                buf.push(b'\n');
+            }
+            Format::Pretty => {
+                serde_json::to_writer_pretty(&mut *buf, doc)?;
+                buf.push(b'\n');
+            }
+            Format::JsonArray => {
+                buf.extend_from_slice(if self.count == 0 { b"[" } else { b",\n" });
+                serde_json::to_writer(&mut *buf, doc)?;
+            }
+            Format::Csv => self.encode_csv(doc, buf)?,
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    // Encode a CSV row of the document, preceded by a header row if this is
+    // the first document. Acknowledgements are skipped, and documents which
+    // aren't objects are an error. Properties which aren't columns are ignored,
+    // string values are written as-is, and other values are written as JSON.
+    fn encode_csv(&mut self, doc: &Value, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        if doc.pointer("/_meta/ack") == Some(&Value::Bool(true)) {
+            return Ok(());
+        }
+        let Value::Object(map) = doc else {
+            anyhow::bail!("cannot encode document {doc} as a CSV row because it's not an object");
+        };
+        let mut w = csv::WriterBuilder::new()
+            .delimiter(self.csv_delimiter)
+            .from_writer(buf);
+
+        let columns = match &self.csv_columns {
+            Some(columns) => columns,
+            None => {
+                let columns = self.csv_columns.insert(map.keys().cloned().collect());
+                if self.csv_header {
+                    w.write_record(&*columns)?;
+                }
+                columns
+            }
+        };
+
+        let row = columns.iter().map(|column| match map.get(column) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.clone(),
+            Some(value) => value.to_string(),
+        });
+        w.write_record(row)?;
+        w.flush()?;
+
+        Ok(())
+    }
+
+    /// Encode the end of the output into `buf`, once all documents are encoded.
+    pub fn finish(&self, buf: &mut Vec<u8>) {
+        if let Format::JsonArray = self.format {
+            if self.count == 0 {
+                buf.push(b'[');
+            }
+            buf.extend_from_slice(b"]\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn encode_all(format: Format, docs: &[Value]) -> String {
+        encode_with(Encoder::new(format), docs)
+    }
+
+    fn encode_with(mut encoder: Encoder, docs: &[Value]) -> String {
+        let mut out = Vec::new();
+
+        for doc in docs {
+            encoder.encode(doc, &mut out).unwrap();
+        }
+        encoder.finish(&mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_formats() {
+        let docs = vec![
+            json!({"key": "a", "nested": {"value": 1}}),
+            json!({"key": "b", "nested": {"value": 2}}),
+        ];
+
+        assert_eq!(
+            encode_all(Format::Ndjson, &docs),
+            "{\"key\":\"a\",\"nested\":{\"value\":1}}\n{\"key\":\"b\",\"nested\":{\"value\":2}}\n"
+        );
+
+        let array = encode_all(Format::JsonArray, &docs);
+        assert_eq!(
+            array,
+            "[{\"key\":\"a\",\"nested\":{\"value\":1}},\n{\"key\":\"b\",\"nested\":{\"value\":2}}]\n"
+        );
+        assert_eq!(serde_json::from_str::<Value>(&array).unwrap(), json!(docs));
+
+        // An empty output is still a valid JSON array.
+        let empty = encode_all(Format::JsonArray, &[]);
+        assert_eq!(serde_json::from_str::<Value>(&empty).unwrap(), json!([]));
+
+        let pretty = encode_all(Format::Pretty, &docs[..1]);
+        assert_eq!(
+            pretty,
+            "{\n  \"key\": \"a\",\n  \"nested\": {\n    \"value\": 1\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let docs = vec![
+            json!({"id": 1, "name": "anvil", "tags": ["heavy"], "note": "a, \"quoted\" note"}),
+            json!({"id": 2, "name": "rocket", "extra": true}),
+        ];
+
+        let csv = encode_all(Format::Csv, &docs);
+        assert_eq!(
+            csv,
+            "id,name,note,tags\n1,anvil,\"a, \"\"quoted\"\" note\",\"[\"\"heavy\"\"]\"\n2,rocket,,\n"
+        );
+
+        // Without a header, the first line is a row.
+        let csv = encode_with(
+            Encoder::new(Format::Csv).with_csv_options(false, b','),
+            &docs,
+        );
+        assert!(csv.starts_with("1,anvil,"));
+        assert_eq!(csv.lines().count(), 2);
+
+        // Tab-separated values.
+        let tsv = encode_with(
+            Encoder::new(Format::Csv).with_csv_options(true, b'\t'),
+            &docs[1..],
+        );
+        assert_eq!(tsv, "extra\tid\tname\ntrue\t2\trocket\n");
+
+        // Acknowledgements don't determine the header, and aren't written.
+        let ack = json!({"_meta": {"uuid": "a-b-c", "ack": true}});
+        let csv = encode_all(Format::Csv, &[ack.clone(), docs[1].clone(), ack]);
+        assert_eq!(csv, "extra,id,name\ntrue,2,rocket\n");
+
+        // Documents which aren't objects cannot be written as rows.
+        let mut encoder = Encoder::new(Format::Csv);
+        let mut out = Vec::new();
+        encoder.encode(&docs[0], &mut out).unwrap();
+        let err = encoder.encode(&json!([1, 2]), &mut out).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot encode document [1,2] as a CSV row because it's not an object"
+        );
+
+        assert_eq!(parse_csv_delimiter("\t"), Ok(b'\t'));
+        assert!(parse_csv_delimiter(",,").is_err());
+        assert!(parse_csv_delimiter("→").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use itertools::Itertools;
+use jaq_core::{parse, Ctx, Definitions, RcIter, Val};
+
+/// JqFilter is a compiled jq program which transforms documents.
+pub struct JqFilter {
+    filter: jaq_core::Filter,
+}
+
+impl JqFilter {
+    /// Compile a jq program, which may use filters of the jq standard library.
+    pub fn compile(program: &str) -> anyhow::Result<Self> {
+        let mut defs = Definitions::core();
+        let mut errs = Vec::new();
+
+        for def in jaq_std::std() {
+            defs.insert(def, &mut errs);
+        }
+        let (main, parse_errs) = parse::parse(program, parse::main());
+
+        let Some(main) = main.filter(|_| parse_errs.is_empty()) else {
+            anyhow::bail!(
+                "failed to parse jq program '{program}': {}",
+                parse_errs.iter().join(", ")
+            );
+        };
+        let filter = defs.finish(main, Vec::new(), &mut errs);
+
+        if !errs.is_empty() {
+            anyhow::bail!(
+                "failed to compile jq program '{program}': {}",
+                errs.iter().join(", ")
+            );
+        }
+        Ok(Self { filter })
+    }
+
+    /// Apply the program to a document, returning each of its outputs.
+    /// Outputs which are `null` are filtered out.
+    pub fn apply(&self, doc: serde_json::Value) -> anyhow::Result<Vec<serde_json::Value>> {
+        let inputs = RcIter::new(std::iter::empty());
+        let mut out = Vec::new();
+
+        for result in self.filter.run(Ctx::new([], &inputs), Val::from(doc)) {
+            match result.map_err(|err| anyhow::anyhow!("jq program failed: {err}"))? {
+                Val::Null => (),
+                value => out.push(value.into()),
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_jq_filter() {
+        let docs = vec![
+            json!({"key": "a", "value": 1}),
+            json!({"key": "b", "value": 2}),
+            json!({"value": 3}),
+            json!({"key": "c", "value": 4}),
+        ];
+
+        let filter = JqFilter::compile(".key").unwrap();
+        let out = docs
+            .iter()
+            .map(|doc| filter.apply(doc.clone()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            out,
+            vec![
+                vec![json!("a")],
+                vec![json!("b")],
+                vec![], // Null outputs are filtered.
+                vec![json!("c")],
+            ]
+        );
+
+        // Programs may produce multiple outputs, and use the standard library.
+        let filter = JqFilter::compile("select(.value > 2) | .key, .value").unwrap();
+        assert_eq!(
+            filter.apply(docs[3].clone()).unwrap(),
+            vec![json!("c"), json!(4)]
+        );
+        assert!(filter.apply(docs[0].clone()).unwrap().is_empty());
+
+        // Invalid programs fail to compile.
+        assert!(JqFilter::compile(".key |").is_err());
+        assert!(JqFilter::compile("not_a_function(1)").is_err());
+        // Runtime errors are surfaced.
+        assert!(JqFilter::compile(".key + 1")
+            .unwrap()
+            .apply(docs[0].clone())
+            .is_err());
+    }
+}
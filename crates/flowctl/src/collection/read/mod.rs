@@ -1,6 +1,13 @@
+mod format;
+mod jq;
+mod pipeline;
+mod project;
+mod sample;
+
 use crate::dataplane::{self};
-use crate::{collection::CollectionJournalSelector, output::OutputType};
+use crate::{api_exec, collection::CollectionJournalSelector, output::OutputType};
 use anyhow::Context;
+use futures::{AsyncBufReadExt, TryStreamExt};
 use journal_client::{
     broker,
     fragments::FragmentIter,
@@ -9,6 +16,7 @@ use journal_client::{
     Client,
 };
 use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
 use tokio_util::compat::FuturesAsyncReadCompatExt;
 
 #[derive(clap::Args, Debug, Clone)]
@@ -17,7 +25,7 @@ pub struct SchemaInferenceArgs {
     pub selector: CollectionJournalSelector,
 }
 
-#[derive(clap::Args, Default, Debug, Clone)]
+#[derive(clap::Args, Debug, Clone)]
 pub struct ReadArgs {
     #[clap(flatten)]
     pub selector: CollectionJournalSelector,
@@ -30,10 +38,67 @@ pub struct ReadArgs {
     /// the default.
     #[clap(long)]
     pub uncommitted: bool,
+    /// Output only a deterministic sample of documents, selected by hashing
+    /// each document's collection key. For example `--sample-rate 0.1` outputs
+    /// approximately one in ten documents. Must be between 0.0 and 1.0.
+    #[clap(long, value_parser = sample::parse_sample_rate)]
+    pub sample_rate: Option<f64>,
+    /// Seed used when sampling documents. Different seeds select different samples.
+    #[clap(long, default_value = "0", requires = "sample_rate")]
+    pub sample_seed: u64,
+    /// Output only these comma-separated fields of each document, discarding
+    /// all others. Nested fields are separated by dots, as in `parent.child`.
+    #[clap(long, value_delimiter = ',')]
+    pub fields: Vec<String>,
+    /// Transform each document using this jq program. Each output of the
+    /// program is written on its own line, and `null` outputs are discarded.
+    /// The program is applied after any `--fields` projection.
+    #[clap(long)]
+    pub jq: Option<String>,
+    /// Format of output documents.
+    #[clap(long, value_enum, default_value = "ndjson")]
+    pub format: format::Format,
+    /// Omit the header row of `--format csv` output.
+    /// This is helpful when concatenating the output of multiple reads.
+    #[clap(long, requires = "format")]
+    pub no_header: bool,
+    /// Delimiter of `--format csv` output, which must be a single character.
+    /// For example, use `--csv-delimiter $'\t'` for tab-separated values.
+    #[clap(long, default_value = ",", requires = "format", value_parser = format::parse_csv_delimiter)]
+    pub csv_delimiter: u8,
+    /// Exit with an error upon the first document which fails to parse.
+    /// By default, such documents are logged to stderr and skipped.
+    #[clap(long)]
+    pub exit_on_error: bool,
+    /// Maximum size in bytes of a message read from the data plane.
+    /// Increase this to read collections having very large documents.
+    #[clap(long, default_value_t = dataplane::DEFAULT_MAX_MESSAGE_SIZE)]
+    pub max_message_size: usize,
     #[clap(skip)]
     pub auth_prefixes: Vec<String>,
 }
 
+// Defaults match those of the command-line arguments.
+impl Default for ReadArgs {
+    fn default() -> Self {
+        Self {
+            selector: Default::default(),
+            bounds: Default::default(),
+            uncommitted: false,
+            sample_rate: None,
+            sample_seed: 0,
+            fields: Vec::new(),
+            jq: None,
+            format: format::Format::default(),
+            no_header: false,
+            csv_delimiter: b',',
+            exit_on_error: false,
+            max_message_size: dataplane::DEFAULT_MAX_MESSAGE_SIZE,
+            auth_prefixes: Vec::new(),
+        }
+    }
+}
+
 /// Common definition for arguments specifying the begin and and bounds of a read command.
 #[derive(clap::Args, Debug, Default, Clone)]
 pub struct ReadBounds {
@@ -57,7 +122,13 @@ pub async fn journal_reader(
         args.auth_prefixes.clone()
     };
     let cp_client = ctx.controlplane_client().await?;
-    let mut data_plane_client = dataplane::journal_client_for(cp_client, auth_prefixes).await?;
+    let mut data_plane_client = dataplane::journal_client_for(
+        cp_client,
+        ctx.journal_client_pool(),
+        auth_prefixes,
+        args.max_message_size,
+    )
+    .await?;
 
     let selector = args.selector.build_label_selector();
     tracing::debug!(?selector, "build label selector");
@@ -129,12 +200,124 @@ pub async fn read_collection(ctx: &mut crate::CliContext, args: &ReadArgs) -> an
         );
     }
 
+    // Clap requires that `--format` is given alongside CSV options, but cannot
+    // check its value.
+    if args.format != format::Format::Csv && (args.no_header || args.csv_delimiter != b',') {
+        anyhow::bail!("--no-header and --csv-delimiter may only be used with --format csv");
+    }
+
+    let sampler = match args.sample_rate {
+        Some(rate) => {
+            let key = fetch_collection_key(ctx, &args.selector.collection).await?;
+            Some(sample::Sampler::new(rate, args.sample_seed, &key))
+        }
+        None => None,
+    };
+
+    let projection = if args.fields.is_empty() {
+        None
+    } else {
+        Some(project::Projection::new(&args.fields))
+    };
+
+    let jq = match &args.jq {
+        Some(program) => Some(jq::JqFilter::compile(program)?),
+        None => None,
+    };
+
     let reader = journal_reader(ctx, args).await?;
 
-    tokio::io::copy(&mut reader.compat(), &mut tokio::io::stdout()).await?;
+    // Documents are copied through unmodified, unless they must be parsed.
+    if !needs_parsing(args) {
+        tokio::io::copy(&mut reader.compat(), &mut tokio::io::stdout())
+            .await
+            .map_err(|err| read_error(err, args.max_message_size))?;
+        return Ok(());
+    }
+
+    let mut pipeline = pipeline::Pipeline {
+        sampler,
+        projection,
+        jq,
+        encoder: format::Encoder::new(args.format)
+            .with_csv_options(!args.no_header, args.csv_delimiter),
+        exit_on_error: args.exit_on_error,
+    };
+    let mut lines = futures::io::BufReader::new(reader).lines();
+    let mut stdout = tokio::io::stdout();
+    let mut buf = Vec::new();
+
+    while let Some(line) = lines
+        .try_next()
+        .await
+        .map_err(|err| read_error(err, args.max_message_size))?
+    {
+        buf.clear();
+        pipeline.process(&line, &mut buf)?;
+        stdout.write_all(&buf).await?;
+    }
+
+    buf.clear();
+    pipeline.finish(&mut buf);
+    stdout.write_all(&buf).await?;
     Ok(())
 }
 
+// Returns true if documents must be parsed in order to be output as `args`
+// requests, rather than being copied through unmodified.
+fn needs_parsing(args: &ReadArgs) -> bool {
+    args.sample_rate.is_some()
+        || !args.fields.is_empty()
+        || args.jq.is_some()
+        || args.format != format::Format::Ndjson
+        || args.exit_on_error
+}
+
+// Map an error of a journal read into an anyhow::Error, adding context if
+// the read failed because a response exceeded the `max_message_size` limit.
+fn read_error(err: std::io::Error, max_message_size: usize) -> anyhow::Error {
+    let too_large = match err
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<journal_client::read::Error>())
+    {
+        Some(journal_client::read::Error::GRPC(status)) => status.code() == tonic::Code::OutOfRange,
+        _ => false,
+    };
+
+    if too_large {
+        anyhow::Error::new(err).context(format!(
+            "reading collection data: a message exceeded the limit of {max_message_size} bytes \
+             (consider a larger --max-message-size)"
+        ))
+    } else {
+        err.into()
+    }
+}
+
+/// Fetch the key of the named collection from its live specification.
+async fn fetch_collection_key(
+    ctx: &mut crate::CliContext,
+    collection: &str,
+) -> anyhow::Result<models::CompositeKey> {
+    #[derive(serde::Deserialize)]
+    struct Row {
+        spec: models::CollectionDef,
+    }
+    let Row { spec } = api_exec::<Row>(
+        ctx.controlplane_client()
+            .await?
+            .from("live_specs_ext")
+            .select("spec")
+            .eq("catalog_name", collection)
+            .eq("spec_type", "collection")
+            .single(),
+    )
+    .await
+    .with_context(|| format!("fetching the key of collection '{collection}'"))?;
+
+    Ok(spec.key)
+}
+
 async fn find_start_offset(
     client: Client,
     journal: String,
@@ -165,3 +348,61 @@ async fn find_start_offset(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_needs_parsing() {
+        assert!(!needs_parsing(&ReadArgs::default()));
+
+        // Each of these options requires that documents be parsed.
+        for args in [
+            ReadArgs {
+                sample_rate: Some(0.5),
+                ..Default::default()
+            },
+            ReadArgs {
+                fields: vec!["id".to_string()],
+                ..Default::default()
+            },
+            ReadArgs {
+                jq: Some(".id".to_string()),
+                ..Default::default()
+            },
+            ReadArgs {
+                format: format::Format::Csv,
+                ..Default::default()
+            },
+            ReadArgs {
+                exit_on_error: true,
+                ..Default::default()
+            },
+        ] {
+            assert!(needs_parsing(&args), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn test_csv_options_require_format() {
+        use clap::Parser;
+
+        #[derive(clap::Parser, Debug)]
+        struct Cmd {
+            #[clap(flatten)]
+            args: ReadArgs,
+        }
+        let parse = |argv: &[&str]| {
+            Cmd::try_parse_from([&["read", "--collection", "acmeCo/anvils"], argv].concat())
+        };
+
+        let cmd = parse(&["--format", "csv", "--no-header", "--csv-delimiter", ";"]).unwrap();
+        assert!(cmd.args.no_header);
+        assert_eq!(cmd.args.csv_delimiter, b';');
+        assert_eq!(parse(&[]).unwrap().args.csv_delimiter, b',');
+
+        assert!(parse(&["--no-header"]).is_err());
+        assert!(parse(&["--csv-delimiter", ";"]).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use super::{format, jq, project, sample};
+use anyhow::Context;
+
+/// Pipeline parses lines of collection documents, and then samples, projects,
+/// transforms, and encodes each document for output.
+pub struct Pipeline {
+    pub sampler: Option<sample::Sampler>,
+    pub projection: Option<project::Projection>,
+    pub jq: Option<jq::JqFilter>,
+    pub encoder: format::Encoder,
+    /// Fail on the first document which cannot be parsed,
+    /// rather than logging and skipping over it.
+    pub exit_on_error: bool,
+}
+
+impl Pipeline {
+    /// Process a single `line` of a collection journal,
+    /// appending its encoded output documents to `buf`.
+    pub fn process(&mut self, line: &str, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        let doc: serde_json::Value = match serde_json::from_str(line) {
+            Ok(doc) => doc,
+            Err(err) if self.exit_on_error => {
+                return Err(err).with_context(|| format!("parsing collection document {line:?}"));
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, document = %line, "skipping collection document which failed to parse");
+                return Ok(());
+            }
+        };
+
+        if let Some(sampler) = self.sampler.as_mut() {
+            if !sampler.sample(&doc) {
+                return Ok(());
+            }
+        }
+        let doc = match &self.projection {
+            Some(projection) => projection.project(&doc),
+            None => doc,
+        };
+        let outputs = match &self.jq {
+            Some(jq) => jq.apply(doc)?,
+            None => vec![doc],
+        };
+
+        for doc in outputs {
+            self.encoder.encode(&doc, buf)?;
+        }
+        Ok(())
+    }
+
+    /// Append the end of the output to `buf`, once all lines are processed.
+    pub fn finish(&self, buf: &mut Vec<u8>) {
+        self.encoder.finish(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(exit_on_error: bool, lines: &[&str]) -> anyhow::Result<String> {
+        let mut pipeline = Pipeline {
+            sampler: None,
+            projection: Some(project::Projection::new(&["id"])),
+            jq: None,
+            encoder: format::Encoder::new(format::Format::Ndjson),
+            exit_on_error,
+        };
+        let mut buf = Vec::new();
+
+        for line in lines {
+            pipeline.process(line, &mut buf)?;
+        }
+        pipeline.finish(&mut buf);
+        Ok(String::from_utf8(buf).unwrap())
+    }
+
+    #[test]
+    fn test_malformed_documents() {
+        let lines = [
+            r#"{"id": 1, "extra": true}"#,
+            r#"{"id": 2, "trunc"#,
+            r#"{"id": 3}"#,
+        ];
+
+        // By default, malformed documents are skipped.
+        assert_eq!(run(false, &lines).unwrap(), "{\"id\":1}\n{\"id\":3}\n");
+
+        // With `exit_on_error`, the first malformed document is an error
+        // which includes its content.
+        let err = run(true, &lines).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            r#"parsing collection document "{\"id\": 2, \"trunc": EOF while parsing a string at line 1 column 16"#
+        );
+    }
+}
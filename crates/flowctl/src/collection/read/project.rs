@@ -0,0 +1,78 @@
+use serde_json::{Map, Value};
+
+/// Projection retains only selected fields of documents, discarding all others.
+/// Fields are object properties, and nested properties are separated by dots
+/// (for example `parent.child`).
+pub struct Projection {
+    paths: Vec<Vec<String>>,
+}
+
+impl Projection {
+    pub fn new<S: AsRef<str>>(fields: &[S]) -> Self {
+        let paths = fields
+            .iter()
+            .map(|field| field.as_ref().split('.').map(str::to_string).collect())
+            .collect();
+
+        Self { paths }
+    }
+
+    /// Project the document into a new document having only the selected fields.
+    /// Fields which aren't present in the document are omitted.
+    pub fn project(&self, doc: &Value) -> Value {
+        let mut out = Value::Object(Map::new());
+
+        'paths: for path in &self.paths {
+            let Some(value) = path.iter().try_fold(doc, |doc, prop| doc.get(prop)) else {
+                continue;
+            };
+            let (last, parents) = path.split_last().expect("fields are never empty");
+
+            let mut cur = &mut out;
+            for prop in parents {
+                match cur {
+                    Value::Object(map) => {
+                        cur = map
+                            .entry(prop.clone())
+                            .or_insert_with(|| Value::Object(Map::new()));
+                    }
+                    // A parent of this field was itself selected, and already
+                    // includes the field.
+                    _ => continue 'paths,
+                }
+            }
+            if let Value::Object(map) = cur {
+                map.insert(last.clone(), value.clone());
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_projection() {
+        let doc = json!({
+            "a": 1, "b": "two", "c": 3, "d": 4, "e": 5,
+            "f": 6, "g": 7, "h": 8, "i": 9, "j": 10,
+        });
+        let projected = Projection::new(&["b", "h"]).project(&doc);
+        assert_eq!(projected, json!({"b": "two", "h": 8}));
+
+        let doc = json!({
+            "parent": {"child": 1, "sibling": 2},
+            "other": {"child": 3},
+            "top": true,
+        });
+        let projected = Projection::new(&["parent.child", "top", "missing.field"]).project(&doc);
+        assert_eq!(projected, json!({"parent": {"child": 1}, "top": true}));
+
+        // Selecting both a parent and its child includes the entire parent.
+        let projected = Projection::new(&["other", "other.child"]).project(&doc);
+        assert_eq!(projected, json!({"other": {"child": 3}}));
+    }
+}
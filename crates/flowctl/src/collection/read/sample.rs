@@ -0,0 +1,90 @@
+/// Sampler deterministically selects a fraction of collection documents.
+/// Each document's packed collection key is hashed by XXH3 with the sample
+/// seed, and the document is included only if its hash falls within the
+/// sample rate. Documents having the same key are therefore always sampled
+/// together, and the same seed always produces the same sample, including
+/// across releases and platforms.
+pub struct Sampler {
+    extractors: Vec<doc::Extractor>,
+    seed: u64,
+    threshold: u64,
+    buf: bytes::BytesMut,
+}
+
+impl Sampler {
+    /// Build a Sampler which includes approximately `rate` (0.0 to 1.0) of
+    /// documents, using the given collection `key` pointers and `seed`.
+    pub fn new<S: AsRef<str>>(rate: f64, seed: u64, key: &[S]) -> Self {
+        let policy = doc::SerPolicy::noop();
+        let extractors = key
+            .iter()
+            .map(|ptr| doc::Extractor::new(ptr.as_ref(), &policy))
+            .collect();
+
+        Self {
+            extractors,
+            seed,
+            threshold: (rate * 1000.0) as u64,
+            buf: bytes::BytesMut::new(),
+        }
+    }
+
+    /// Returns true if the document should be included in the sample.
+    pub fn sample(&mut self, doc: &serde_json::Value) -> bool {
+        let packed = doc::Extractor::extract_all(doc, &self.extractors, &mut self.buf);
+        xxhash_rust::xxh3::xxh3_64_with_seed(&packed, self.seed) % 1000 < self.threshold
+    }
+}
+
+/// Parse a --sample-rate argument, which must be within [0.0, 1.0].
+pub fn parse_sample_rate(s: &str) -> Result<f64, String> {
+    let rate: f64 = s.parse().map_err(|err| format!("{err}"))?;
+
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("sample rate {rate} must be between 0.0 and 1.0"));
+    }
+    Ok(rate)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sample_rate() {
+        let docs: Vec<_> = (0..1000).map(|i| json!({"id": i, "v": "x"})).collect();
+
+        let mut sampler = Sampler::new(0.1, 0, &["/id"]);
+        let sampled: Vec<_> = docs.iter().filter(|d| sampler.sample(d)).collect();
+        assert!(
+            (50..150).contains(&sampled.len()),
+            "sampled {} documents",
+            sampled.len()
+        );
+
+        // Sampling is deterministic.
+        let mut again = Sampler::new(0.1, 0, &["/id"]);
+        let resampled: Vec<_> = docs.iter().filter(|d| again.sample(d)).collect();
+        assert_eq!(sampled, resampled);
+
+        // A different seed produces a different sample.
+        let mut seeded = Sampler::new(0.1, 42, &["/id"]);
+        let reseeded: Vec<_> = docs.iter().filter(|d| seeded.sample(d)).collect();
+        assert_ne!(sampled, reseeded);
+
+        // Rates of zero and one include nothing and everything.
+        let mut none = Sampler::new(0.0, 0, &["/id"]);
+        assert!(docs.iter().all(|d| !none.sample(d)));
+        let mut all = Sampler::new(1.0, 0, &["/id"]);
+        assert!(docs.iter().all(|d| all.sample(d)));
+    }
+
+    #[test]
+    fn test_parse_sample_rate() {
+        assert_eq!(parse_sample_rate("0.25"), Ok(0.25));
+        assert!(parse_sample_rate("1.5").is_err());
+        assert!(parse_sample_rate("-0.1").is_err());
+        assert!(parse_sample_rate("nope").is_err());
+    }
+}
@@ -22,6 +22,13 @@ impl Debug for Client {
     }
 }
 
+impl Client {
+    /// Wrap a Postgrest client of the control-plane API.
+    pub fn new(client: postgrest::Postgrest) -> Self {
+        Self(Arc::new(client))
+    }
+}
+
 impl Deref for Client {
     type Target = postgrest::Postgrest;
 
@@ -64,7 +71,7 @@ pub(crate) async fn new_client(ctx: &mut CliContext) -> anyhow::Result<Client> {
             }
             let client =
                 client.insert_header("Authorization", format!("Bearer {}", &api.access_token));
-            Ok(Client(Arc::new(client)))
+            Ok(Client::new(client))
         }
         None => {
             // If there has been no prior login, but FLOW_AUTH_TOKEN is available, we use that to
@@ -89,13 +96,13 @@ pub(crate) async fn new_client(ctx: &mut CliContext) -> anyhow::Result<Client> {
 
                 let client = client
                     .insert_header("Authorization", format!("Bearer {}", response.access_token));
-                Ok(Client(Arc::new(client)))
+                Ok(Client::new(client))
             } else {
                 tracing::warn!("You are not authenticated. Run `auth login` to login to Flow.");
 
                 let client = postgrest::Postgrest::new(ENDPOINT);
                 let client = client.insert_header("apikey", PUBLIC_TOKEN);
-                Ok(Client(Arc::new(client)))
+                Ok(Client::new(client))
             }
         }
     }
@@ -134,7 +141,7 @@ fn check_access_token(access_token: &str) -> anyhow::Result<JWT> {
     Ok(jwt)
 }
 
-const FLOW_AUTH_TOKEN: &str = "FLOW_AUTH_TOKEN";
+pub(crate) const FLOW_AUTH_TOKEN: &str = "FLOW_AUTH_TOKEN";
 #[derive(Deserialize)]
 struct JWT {
     exp: i64,
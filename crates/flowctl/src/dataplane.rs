@@ -1,6 +1,8 @@
 use crate::controlplane;
 use anyhow::Context;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
 pub struct DataPlaneAccess {
@@ -44,10 +46,18 @@ pub async fn fetch_data_plane_access_token(
     Ok(access)
 }
 
+/// Default maximum size of a message received from the data plane,
+/// which is also the default of gRPC.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
 /// Returns an authenticated journal client that's authorized to the given prefixes.
+/// The client fails any response message which is larger than `max_message_size`.
+/// It shares the `pool` connection to its data-plane gateway, if there is one.
 pub async fn journal_client_for(
     cp_client: controlplane::Client,
+    pool: &JournalClientPool,
     prefixes: Vec<String>,
+    max_message_size: usize,
 ) -> anyhow::Result<journal_client::Client> {
     let DataPlaneAccess {
         auth_token,
@@ -55,8 +65,247 @@ pub async fn journal_client_for(
     } = fetch_data_plane_access_token(cp_client, prefixes).await?;
     tracing::debug!(%gateway_url, "acquired data-plane-gateway access token");
 
-    let client =
-        journal_client::connect_journal_client(gateway_url.clone(), Some(auth_token)).await?;
-    tracing::debug!(%gateway_url, "connected data-plane client");
-    Ok(client)
+    pool.client(gateway_url, auth_token, max_message_size).await
+}
+
+/// Client of the data-plane Shard API, which authorizes its requests with a bearer token.
+pub type ShardClient = proto_grpc::consumer::shard_client::ShardClient<
+    tonic::codegen::InterceptedService<tonic::transport::Channel, journal_client::AuthHeader>,
+>;
+
+/// JournalClientPool holds a connection to each data-plane gateway endpoint,
+/// which is shared by all journal clients of that endpoint. Each client
+/// has its own authorization, but multiplexes its requests over the pooled
+/// connection. Clones of a JournalClientPool share the same connections.
+#[derive(Clone, Debug, Default)]
+pub struct JournalClientPool(Arc<Mutex<PoolState>>);
+
+#[derive(Debug, Default)]
+struct PoolState {
+    channels: HashMap<String, tonic::transport::Channel>,
+    stats: PoolStats,
+}
+
+/// Counts of journal clients which used a pooled connection (hits),
+/// or which required a new connection (misses).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl JournalClientPool {
+    /// Returns a journal client of the `gateway_url` endpoint which authorizes its
+    /// requests with `auth_token`, connecting to the endpoint if it's not yet pooled.
+    pub async fn client(
+        &self,
+        gateway_url: String,
+        auth_token: String,
+        max_message_size: usize,
+    ) -> anyhow::Result<journal_client::Client> {
+        let auth_header = journal_client::AuthHeader::bearer(&auth_token)?;
+        let channel = self.channel(gateway_url).await?;
+
+        Ok(journal_client::client_with_auth(channel, auth_header)
+            .max_decoding_message_size(max_message_size))
+    }
+
+    /// Returns a shard client of the `gateway_url` endpoint which authorizes its
+    /// requests with `auth_token`, connecting to the endpoint if it's not yet pooled.
+    pub async fn shard_client(
+        &self,
+        gateway_url: String,
+        auth_token: String,
+    ) -> anyhow::Result<ShardClient> {
+        let auth_header = journal_client::AuthHeader::bearer(&auth_token)?;
+        let channel = self.channel(gateway_url).await?;
+
+        Ok(proto_grpc::consumer::shard_client::ShardClient::with_interceptor(channel, auth_header))
+    }
+
+    async fn channel(&self, gateway_url: String) -> anyhow::Result<tonic::transport::Channel> {
+        let pooled = self.0.lock().unwrap().channels.get(&gateway_url).cloned();
+
+        // The lock isn't held while connecting. If another client connected to
+        // the endpoint in the meantime, its connection is kept and ours is dropped.
+        let channel = match pooled {
+            Some(channel) => {
+                self.0.lock().unwrap().stats.hits += 1;
+                channel
+            }
+            None => {
+                let channel = journal_client::connect_channel(gateway_url.clone()).await?;
+                tracing::debug!(%gateway_url, "connected data-plane gateway");
+
+                let mut state = self.0.lock().unwrap();
+                state.stats.misses += 1;
+                state.channels.entry(gateway_url).or_insert(channel).clone()
+            }
+        };
+        Ok(channel)
+    }
+
+    /// Returns the hit and miss counts of clients of this pool.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.0.lock().unwrap().stats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{stream::BoxStream, StreamExt};
+    use proto_gazette::broker;
+    use proto_grpc::broker::journal_server::{Journal, JournalServer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tonic::{Request, Response, Status};
+
+    // Journal server which responds to every read with a single document of
+    // the given size. Other RPCs aren't used.
+    struct LargeDocument(usize);
+
+    #[tonic::async_trait]
+    impl Journal for LargeDocument {
+        type ReadStream = BoxStream<'static, Result<broker::ReadResponse, Status>>;
+        type ReplicateStream = BoxStream<'static, Result<broker::ReplicateResponse, Status>>;
+
+        async fn read(
+            &self,
+            _request: Request<broker::ReadRequest>,
+        ) -> Result<Response<Self::ReadStream>, Status> {
+            let content = format!("{{\"big\":\"{}\"}}\n", "x".repeat(self.0));
+            let response = broker::ReadResponse {
+                content: content.into(),
+                ..Default::default()
+            };
+            Ok(Response::new(futures::stream::iter([Ok(response)]).boxed()))
+        }
+
+        async fn list(
+            &self,
+            _request: Request<broker::ListRequest>,
+        ) -> Result<Response<broker::ListResponse>, Status> {
+            Err(Status::unimplemented("list"))
+        }
+        async fn apply(
+            &self,
+            _request: Request<broker::ApplyRequest>,
+        ) -> Result<Response<broker::ApplyResponse>, Status> {
+            Err(Status::unimplemented("apply"))
+        }
+        async fn append(
+            &self,
+            _request: Request<tonic::Streaming<broker::AppendRequest>>,
+        ) -> Result<Response<broker::AppendResponse>, Status> {
+            Err(Status::unimplemented("append"))
+        }
+        async fn replicate(
+            &self,
+            _request: Request<tonic::Streaming<broker::ReplicateRequest>>,
+        ) -> Result<Response<Self::ReplicateStream>, Status> {
+            Err(Status::unimplemented("replicate"))
+        }
+        async fn list_fragments(
+            &self,
+            _request: Request<broker::FragmentsRequest>,
+        ) -> Result<Response<broker::FragmentsResponse>, Status> {
+            Err(Status::unimplemented("list_fragments"))
+        }
+    }
+
+    async fn read_document(
+        pool: &JournalClientPool,
+        gateway_url: &str,
+        max_message_size: usize,
+    ) -> Result<usize, Status> {
+        let mut client = pool
+            .client(
+                gateway_url.to_string(),
+                "a-token".to_string(),
+                max_message_size,
+            )
+            .await
+            .unwrap();
+
+        let mut stream = client
+            .read(broker::ReadRequest::default())
+            .await?
+            .into_inner();
+        let response = stream.message().await?.expect("a response");
+        Ok(response.content.len())
+    }
+
+    // Serve a LargeDocument of `size` on an ephemeral port, returning its URL
+    // and a count of the connections which it has accepted.
+    async fn serve(size: usize) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway_url = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        let counter = connections.clone();
+        let incoming = futures::stream::unfold(listener, move |listener| {
+            let counter = counter.clone();
+            async move {
+                let stream = listener.accept().await.map(|(stream, _)| stream);
+                counter.fetch_add(1, Ordering::SeqCst);
+                Some((stream, listener))
+            }
+        });
+
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(JournalServer::new(LargeDocument(size)))
+                .serve_with_incoming(incoming),
+        );
+        (gateway_url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_max_message_size() {
+        let (gateway_url, _) = serve(5 * 1024 * 1024).await;
+        let pool = JournalClientPool::default();
+
+        // A 5MB document exceeds the default limit.
+        let status = read_document(&pool, &gateway_url, DEFAULT_MAX_MESSAGE_SIZE)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::OutOfRange);
+        assert!(status.message().contains("the limit is: 4194304 bytes"));
+
+        // But is read with a larger limit.
+        let len = read_document(&pool, &gateway_url, 10485760).await.unwrap();
+        assert_eq!(len, 5 * 1024 * 1024 + 11);
+    }
+
+    #[tokio::test]
+    async fn test_pool_reuses_connections() {
+        let (gateway_url, connections) = serve(16).await;
+        let pool = JournalClientPool::default();
+
+        // Two reads of the same endpoint share a single connection.
+        for _ in 0..2 {
+            let len = read_document(&pool, &gateway_url, DEFAULT_MAX_MESSAGE_SIZE)
+                .await
+                .unwrap();
+            assert_eq!(len, 27);
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.pool_stats(), PoolStats { hits: 1, misses: 1 });
+
+        // Clones of the pool also share it.
+        let clone = pool.clone();
+        read_document(&clone, &gateway_url, DEFAULT_MAX_MESSAGE_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.pool_stats(), PoolStats { hits: 2, misses: 1 });
+
+        // A distinct pool has its own connection.
+        let other = JournalClientPool::default();
+        read_document(&other, &gateway_url, DEFAULT_MAX_MESSAGE_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+        assert_eq!(other.pool_stats(), PoolStats { hits: 0, misses: 1 });
+    }
 }
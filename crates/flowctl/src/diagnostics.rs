@@ -0,0 +1,374 @@
+use crate::api_exec;
+use crate::config::ENDPOINT;
+use anyhow::Context;
+use serde::Serialize;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+
+#[derive(Debug, clap::Args)]
+#[clap(rename_all = "kebab-case")]
+pub struct Diagnostics {
+    /// Maximum duration of each individual check.
+    #[clap(long, default_value = "5s")]
+    pub timeout: humantime::Duration,
+}
+
+/// Check is the outcome of a single connectivity check.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Check {
+    pub name: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status", content = "reason")]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    /// The check was skipped because a check it depends on failed.
+    Skipped,
+}
+
+impl crate::output::CliOutput for Check {
+    type TableAlt = ();
+    type CellValue = String;
+
+    fn table_headers(_alt: Self::TableAlt) -> Vec<&'static str> {
+        vec!["Check", "Result"]
+    }
+
+    fn into_table_row(self, _alt: Self::TableAlt) -> Vec<Self::CellValue> {
+        let result = match self.outcome {
+            Outcome::Pass => "PASS".to_string(),
+            Outcome::Fail(reason) => format!("FAIL: {reason}"),
+            Outcome::Skipped => "SKIPPED".to_string(),
+        };
+        vec![self.name, result]
+    }
+}
+
+impl Diagnostics {
+    pub async fn run(&self, ctx: &mut crate::CliContext) -> anyhow::Result<()> {
+        let endpoint = match &ctx.config().api {
+            Some(api) => api.endpoint.clone(),
+            None => url::Url::parse(ENDPOINT).unwrap(),
+        };
+        let timeout: Duration = self.timeout.into();
+
+        let mut checks = check_endpoint(&endpoint, timeout).await;
+        let reachable = checks.iter().all(|check| check.outcome == Outcome::Pass);
+
+        step(
+            &mut checks,
+            "authenticate with the control plane".to_string(),
+            reachable,
+            timeout,
+            authenticate(ctx),
+        )
+        .await;
+
+        ctx.write_all(checks, ())
+    }
+}
+
+/// Check connectivity to the control plane `endpoint`: that its host resolves,
+/// that a TCP connection can be established, that a TLS handshake succeeds
+/// (for https endpoints only), and that it responds to an unauthenticated
+/// gRPC ping. Checks which depend on a failed check are skipped.
+pub async fn check_endpoint(endpoint: &url::Url, timeout: Duration) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let host = endpoint.host_str().unwrap_or_default().to_string();
+    let port = endpoint.port_or_known_default().unwrap_or(443);
+
+    let addrs = step(
+        &mut checks,
+        format!("resolve {host}"),
+        true,
+        timeout,
+        resolve(host.clone(), port),
+    )
+    .await;
+
+    let stream = step(
+        &mut checks,
+        format!("connect to {host}:{port}"),
+        addrs.is_some(),
+        timeout,
+        connect(addrs.unwrap_or_default()),
+    )
+    .await;
+
+    let secured = if endpoint.scheme() == "https" {
+        let connected = stream.is_some();
+        // The handshake is over the connection of the prior check,
+        // and is polled only if that connection was established.
+        let handshake = async {
+            let stream = stream.expect("handshake runs only if connected");
+            handshake(&host, stream).await
+        };
+        step(
+            &mut checks,
+            format!("TLS handshake with {host}"),
+            connected,
+            timeout,
+            handshake,
+        )
+        .await
+        .is_some()
+    } else {
+        stream.is_some()
+    };
+
+    step(
+        &mut checks,
+        format!("gRPC ping {}", endpoint.origin().ascii_serialization()),
+        secured,
+        timeout,
+        ping(endpoint),
+    )
+    .await;
+
+    checks
+}
+
+// Run a single check if `prior_ok`, recording its Outcome into `checks`.
+async fn step<T, F>(
+    checks: &mut Vec<Check>,
+    name: String,
+    prior_ok: bool,
+    timeout: Duration,
+    check: F,
+) -> Option<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    if !prior_ok {
+        checks.push(Check {
+            name,
+            outcome: Outcome::Skipped,
+        });
+        return None;
+    }
+
+    let (outcome, out) = match tokio::time::timeout(timeout, check).await {
+        Ok(Ok(out)) => (Outcome::Pass, Some(out)),
+        Ok(Err(err)) => (Outcome::Fail(format!("{err:#}")), None),
+        Err(_) => (
+            Outcome::Fail(format!(
+                "timed out after {}",
+                humantime::format_duration(timeout)
+            )),
+            None,
+        ),
+    };
+    checks.push(Check { name, outcome });
+    out
+}
+
+async fn resolve(host: String, port: u16) -> anyhow::Result<Vec<SocketAddr>> {
+    let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .with_context(|| format!("failed to resolve {host}"))?
+        .collect();
+
+    if addrs.is_empty() {
+        anyhow::bail!("{host} resolved to no addresses");
+    }
+    Ok(addrs)
+}
+
+async fn connect(addrs: Vec<SocketAddr>) -> anyhow::Result<TcpStream> {
+    let stream = TcpStream::connect(addrs.as_slice())
+        .await
+        .with_context(|| format!("failed to connect to any of {addrs:?}"))?;
+
+    tracing::debug!(peer = ?stream.peer_addr().ok(), "connected");
+    Ok(stream)
+}
+
+async fn handshake(host: &str, stream: TcpStream) -> anyhow::Result<()> {
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls_native_certs::load_native_certs().context("loading native certificates")? {
+        // Skip certificates which rustls can't parse, as tonic does.
+        let _ = roots.add(&rustls::Certificate(cert.0));
+    }
+    let config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    let server_name = rustls::ServerName::try_from(host)
+        .with_context(|| format!("{host} is not a valid TLS server name"))?;
+    let stream = tokio_rustls::TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+        .context("TLS handshake failed")?;
+
+    let (_, session) = stream.get_ref();
+    tracing::debug!(version = ?session.protocol_version(), "completed TLS handshake");
+    Ok(())
+}
+
+// Ping the endpoint with an unauthenticated gRPC health check over a new
+// Channel. Any response, including an error status, shows that the endpoint
+// is reachable over HTTP/2.
+async fn ping(endpoint: &url::Url) -> anyhow::Result<()> {
+    use tonic::codegen::{http, Service};
+
+    let origin = endpoint.origin().ascii_serialization();
+    let mut channel = tonic::transport::Channel::from_shared(origin)?
+        .connect()
+        .await
+        .context("failed to connect a gRPC channel")?;
+
+    let request = http::Request::post("/grpc.health.v1.Health/Check")
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .body(tonic::body::empty_body())?;
+
+    futures::future::poll_fn(|cx| channel.poll_ready(cx)).await?;
+    let response = channel.call(request).await.context("gRPC ping failed")?;
+
+    tracing::debug!(status = %response.status(), headers = ?response.headers(), "gRPC ping response");
+    Ok(())
+}
+
+async fn authenticate(ctx: &mut crate::CliContext) -> anyhow::Result<()> {
+    if ctx.config().api.is_none() && std::env::var(crate::controlplane::FLOW_AUTH_TOKEN).is_err() {
+        anyhow::bail!("not authenticated; run `flowctl auth login`");
+    }
+    let client = ctx.controlplane_client().await?;
+
+    // Any query of the user's grants requires valid credentials.
+    api_exec::<Vec<serde_json::Value>>(
+        client
+            .from("combined_grants_ext")
+            .select("object_role")
+            .limit(1),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use warp::Filter;
+
+    fn outcomes(checks: &[Check]) -> Vec<&Outcome> {
+        checks.iter().map(|check| &check.outcome).collect()
+    }
+
+    // Serve connections on an ephemeral port by writing an HTTP/1.1 error
+    // and then closing, returning the listener's address.
+    async fn serve_http1() -> SocketAddr {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint() {
+        let timeout = Duration::from_secs(5);
+
+        // An unresolvable host fails, and dependent checks are skipped.
+        let endpoint = url::Url::parse("https://does-not-exist.invalid/rest/v1").unwrap();
+        let checks = check_endpoint(&endpoint, timeout).await;
+
+        assert!(matches!(checks[0].outcome, Outcome::Fail(_)));
+        assert_eq!(
+            outcomes(&checks[1..]),
+            vec![&Outcome::Skipped, &Outcome::Skipped, &Outcome::Skipped]
+        );
+
+        // A host without a listener fails to connect.
+        let port = portpicker::pick_unused_port().unwrap();
+        let endpoint = url::Url::parse(&format!("https://127.0.0.1:{port}/rest/v1")).unwrap();
+        let checks = check_endpoint(&endpoint, timeout).await;
+
+        assert_eq!(checks[0].name, "resolve 127.0.0.1");
+        assert_eq!(checks[0].outcome, Outcome::Pass);
+        assert_eq!(checks[1].name, format!("connect to 127.0.0.1:{port}"));
+        assert!(matches!(checks[1].outcome, Outcome::Fail(_)));
+        assert_eq!(
+            outcomes(&checks[2..]),
+            vec![&Outcome::Skipped, &Outcome::Skipped]
+        );
+
+        // A listener which doesn't speak TLS fails the handshake.
+        let addr = serve_http1().await;
+        let endpoint = url::Url::parse(&format!("https://{addr}/rest/v1")).unwrap();
+        let checks = check_endpoint(&endpoint, timeout).await;
+
+        assert_eq!(outcomes(&checks[..2]), vec![&Outcome::Pass, &Outcome::Pass]);
+        assert_eq!(checks[2].name, "TLS handshake with 127.0.0.1");
+        assert!(
+            matches!(&checks[2].outcome, Outcome::Fail(reason) if reason.starts_with("TLS handshake failed")),
+            "{:?}",
+            checks[2]
+        );
+        assert_eq!(checks[3].name, format!("gRPC ping https://{addr}"));
+        assert_eq!(checks[3].outcome, Outcome::Skipped);
+
+        // Plain http endpoints have no TLS handshake. A listener which doesn't
+        // speak HTTP/2 fails the ping.
+        let addr = serve_http1().await;
+        let endpoint = url::Url::parse(&format!("http://{addr}/rest/v1")).unwrap();
+        let checks = check_endpoint(&endpoint, timeout).await;
+
+        assert_eq!(checks.len(), 3);
+        assert_eq!(outcomes(&checks[..2]), vec![&Outcome::Pass, &Outcome::Pass]);
+        assert_eq!(checks[2].name, format!("gRPC ping http://{addr}"));
+        assert!(matches!(checks[2].outcome, Outcome::Fail(_)));
+
+        // All checks pass against an endpoint which speaks HTTP/2,
+        // even though it responds to the ping with an error.
+        let api = warp::any().map(|| warp::http::StatusCode::NOT_FOUND);
+        let (addr, server) = warp::serve(api).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        let endpoint = url::Url::parse(&format!("http://{addr}/rest/v1")).unwrap();
+
+        let checks = check_endpoint(&endpoint, timeout).await;
+        assert_eq!(
+            outcomes(&checks),
+            vec![&Outcome::Pass, &Outcome::Pass, &Outcome::Pass]
+        );
+
+        // A check which doesn't complete in time fails.
+        let mut checks = Vec::new();
+        let out = step(
+            &mut checks,
+            "slow".to_string(),
+            true,
+            Duration::from_millis(10),
+            async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            },
+        )
+        .await;
+
+        assert!(out.is_none());
+        assert_eq!(
+            checks,
+            vec![Check {
+                name: "slow".to_string(),
+                outcome: Outcome::Fail("timed out after 10ms".to_string()),
+            }]
+        );
+    }
+}
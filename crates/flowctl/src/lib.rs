@@ -10,6 +10,7 @@ mod collection;
 mod config;
 mod controlplane;
 mod dataplane;
+mod diagnostics;
 mod draft;
 mod generate;
 mod local_specs;
@@ -102,6 +103,14 @@ pub enum Command {
     Logs(ops::Logs),
     /// Advanced, low-level, and experimental commands which are less common.
     Raw(raw::Advanced),
+    /// Check connectivity to the Flow control plane.
+    ///
+    /// Diagnostics runs a series of checks which are helpful when troubleshooting
+    /// connection problems: that the control plane host resolves, that a TCP
+    /// connection can be established, that a TLS handshake succeeds, that it
+    /// responds to an unauthenticated gRPC ping, and finally that your
+    /// credentials are valid. Checks which depend on a failed check are skipped.
+    Diagnostics(diagnostics::Diagnostics),
 }
 
 #[derive(Debug)]
@@ -109,6 +118,7 @@ pub struct CliContext {
     config: config::Config,
     output: output::Output,
     controlplane_client: Option<controlplane::Client>,
+    journal_client_pool: dataplane::JournalClientPool,
 }
 
 impl CliContext {
@@ -123,6 +133,11 @@ impl CliContext {
         Ok(self.controlplane_client.clone().unwrap())
     }
 
+    /// Returns the pool of data-plane connections which are shared by journal clients.
+    pub fn journal_client_pool(&self) -> &dataplane::JournalClientPool {
+        &self.journal_client_pool
+    }
+
     pub fn config_mut(&mut self) -> &mut config::Config {
         &mut self.config
     }
@@ -170,6 +185,7 @@ impl Cli {
             config,
             output,
             controlplane_client: None,
+            journal_client_pool: Default::default(),
         };
 
         match &self.cmd {
@@ -181,6 +197,7 @@ impl Cli {
             Command::Draft(draft) => draft.run(&mut context).await,
             Command::Logs(logs) => logs.run(&mut context).await,
             Command::Raw(advanced) => advanced.run(&mut context).await,
+            Command::Diagnostics(diagnostics) => diagnostics.run(&mut context).await,
         }?;
 
         context.config().write(&self.profile)?;
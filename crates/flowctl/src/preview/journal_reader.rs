@@ -8,6 +8,7 @@ use proto_gazette::{broker, consumer};
 #[derive(Clone)]
 pub struct Reader {
     control_plane: crate::controlplane::Client,
+    pool: crate::dataplane::JournalClientPool,
     delay: std::time::Duration,
 }
 
@@ -21,13 +22,18 @@ struct Source {
 
 impl Reader {
     /// Return a new Reader which uses the `control_plane` to identify and read journals from
-    /// their respective collection data planes.
+    /// their respective collection data planes, over connections of the `pool`.
     ///
     /// `delay` is an artificial, injected delay between a read and a subsequent checkpoint.
     /// It emulates back-pressure and encourages amortized transactions and reductions.
-    pub fn new(control_plane: crate::controlplane::Client, delay: std::time::Duration) -> Self {
+    pub fn new(
+        control_plane: crate::controlplane::Client,
+        pool: crate::dataplane::JournalClientPool,
+        delay: std::time::Duration,
+    ) -> Self {
         Self {
             control_plane,
+            pool,
             delay,
         }
     }
@@ -44,8 +50,13 @@ impl Reader {
                 .map(|source| source.collection.clone())
                 .collect();
 
-            let data_plane_client =
-                crate::dataplane::journal_client_for(self.control_plane, access_prefixes).await?;
+            let data_plane_client = crate::dataplane::journal_client_for(
+                self.control_plane,
+                &self.pool,
+                access_prefixes,
+                crate::dataplane::DEFAULT_MAX_MESSAGE_SIZE,
+            )
+            .await?;
 
             // Concurrently list the journals of every Source.
             let journals: Vec<(&Source, Vec<broker::JournalSpec>)> =
@@ -124,7 +124,11 @@ impl Preview {
         } else {
             None
         };
-        let journal_reader = journal_reader::Reader::new(ctx.controlplane_client().await?, delay);
+        let journal_reader = journal_reader::Reader::new(
+            ctx.controlplane_client().await?,
+            ctx.journal_client_pool().clone(),
+            delay,
+        );
 
         let initial_state =
             models::RawValue::from_str(initial_state).context("initial state is not valid JSON")?;
@@ -3,6 +3,7 @@ pub mod list;
 pub mod read;
 
 use proto_grpc::broker::journal_client::JournalClient;
+use std::sync::{Arc, RwLock};
 use tonic::{
     codegen::InterceptedService, metadata::AsciiMetadataValue, service::Interceptor,
     transport::channel::Channel,
@@ -28,18 +29,28 @@ pub async fn connect_journal_client(
     broker_url: String,
     bearer_token: Option<String>,
 ) -> Result<Client, ConnectError> {
-    tracing::trace!("about to connect channel");
-
-    let auth_header = if let Some(token) = bearer_token {
-        Some(
-            format!("Bearer {}", &token)
-                .parse()
-                // parse can only fail if the bearer token contains invalid characters
-                .map_err(|_| ConnectError::InvalidBearerToken)?,
-        )
-    } else {
-        None
+    let auth_header = match bearer_token {
+        Some(token) => AuthHeader::bearer(&token)?,
+        None => AuthHeader::default(),
     };
+    connect_journal_client_with_auth(broker_url, auth_header).await
+}
+
+/// Connect a journal Client which authorizes its requests using the given AuthHeader.
+/// Callers may retain a clone of the AuthHeader in order to later update its bearer
+/// token (for example, after it's refreshed) without re-connecting the Client.
+pub async fn connect_journal_client_with_auth(
+    broker_url: String,
+    auth_header: AuthHeader,
+) -> Result<Client, ConnectError> {
+    let channel = connect_channel(broker_url).await?;
+    Ok(client_with_auth(channel, auth_header))
+}
+
+/// Connect a Channel to the broker. A Channel is cheaply cloned, and its
+/// clones multiplex requests over the same underlying connection.
+pub async fn connect_channel(broker_url: String) -> Result<Channel, ConnectError> {
+    tracing::trace!("about to connect channel");
 
     let channel = Channel::from_shared(broker_url.clone())
         .map_err(|_| ConnectError::BadUri(broker_url))?
@@ -48,20 +59,49 @@ pub async fn connect_journal_client(
         .await?;
 
     tracing::trace!("channel is connected");
-    Ok(JournalClient::with_interceptor(
-        channel,
-        AuthHeader(auth_header),
-    ))
+    Ok(channel)
+}
+
+/// Build a journal Client of a connected Channel,
+/// which authorizes its requests using the given AuthHeader.
+pub fn client_with_auth(channel: Channel, auth_header: AuthHeader) -> Client {
+    JournalClient::with_interceptor(channel, auth_header)
+}
+
+/// AuthHeader is an Interceptor which adds an `authorization` bearer token
+/// header to each request. The token is shared by all clones of an AuthHeader,
+/// and may be updated through any one of them.
+#[derive(Clone, Default)]
+pub struct AuthHeader(Arc<RwLock<Option<AsciiMetadataValue>>>);
+
+impl AuthHeader {
+    /// Build an AuthHeader which authorizes requests with the given bearer token.
+    pub fn bearer(token: &str) -> Result<Self, ConnectError> {
+        Ok(Self(Arc::new(RwLock::new(Some(Self::parse(token)?)))))
+    }
+
+    /// Update the bearer token of this AuthHeader and all of its clones.
+    /// Subsequent requests use the updated token.
+    pub fn update_token(&self, token: &str) -> Result<(), ConnectError> {
+        let bearer = Self::parse(token)?;
+        *self.0.write().unwrap() = Some(bearer);
+        Ok(())
+    }
+
+    fn parse(token: &str) -> Result<AsciiMetadataValue, ConnectError> {
+        format!("Bearer {token}")
+            .parse()
+            // parse can only fail if the bearer token contains invalid characters
+            .map_err(|_| ConnectError::InvalidBearerToken)
+    }
 }
 
-#[derive(Clone)]
-pub struct AuthHeader(Option<AsciiMetadataValue>);
 impl Interceptor for AuthHeader {
     fn call(
         &mut self,
         mut request: tonic::Request<()>,
     ) -> Result<tonic::Request<()>, tonic::Status> {
-        if let Some(bearer) = self.0.as_ref() {
+        if let Some(bearer) = self.0.read().unwrap().as_ref() {
             request
                 .metadata_mut()
                 .insert("authorization", bearer.clone());
@@ -69,3 +109,48 @@ impl Interceptor for AuthHeader {
         Ok(request)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_auth_header() {
+        let authorization = |header: &mut AuthHeader| {
+            header
+                .call(tonic::Request::new(()))
+                .unwrap()
+                .metadata()
+                .get("authorization")
+                .map(|value| value.to_str().unwrap().to_string())
+        };
+
+        // Without a token, no header is added.
+        assert_eq!(authorization(&mut AuthHeader::default()), None);
+
+        let mut header = AuthHeader::bearer("a-token").unwrap();
+        assert_eq!(
+            authorization(&mut header),
+            Some("Bearer a-token".to_string())
+        );
+
+        // Updating the token of a clone updates the token of all clones.
+        let mut cloned = header.clone();
+        header.update_token("refreshed").unwrap();
+        assert_eq!(
+            authorization(&mut cloned),
+            Some("Bearer refreshed".to_string())
+        );
+
+        // Tokens having invalid characters are rejected.
+        assert!(matches!(
+            AuthHeader::bearer("bad\ntoken"),
+            Err(ConnectError::InvalidBearerToken)
+        ));
+        assert!(header.update_token("bad\ntoken").is_err());
+        assert_eq!(
+            authorization(&mut header),
+            Some("Bearer refreshed".to_string())
+        );
+    }
+}
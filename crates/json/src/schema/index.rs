@@ -1,5 +1,5 @@
 use crate::schema::{Annotation, Application, Keyword, Schema};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use thiserror;
 
 #[derive(thiserror::Error, Debug, serde::Serialize)]
@@ -75,6 +75,44 @@ where
         Ok(())
     }
 
+    /// Remove the Schema indexed at `uri`, along with every URI under which
+    /// it or its sub-schemas are indexed (including anchors and aliases).
+    /// Returns false if `uri` isn't indexed.
+    pub fn remove(&mut self, uri: &url::Url) -> bool {
+        let Some(schema) = self.0.get(uri).copied() else {
+            return false;
+        };
+
+        let mut removed = HashSet::new();
+        Self::walk_applications(schema, &mut removed);
+
+        self.0
+            .retain(|_, indexed| !removed.contains(&(*indexed as *const Schema<A>)));
+        true
+    }
+
+    // Collect the schema and all sub-schemas which are indexed by `add`.
+    fn walk_applications(schema: &'s Schema<A>, out: &mut HashSet<*const Schema<A>>) {
+        out.insert(schema);
+
+        for kw in &schema.kw {
+            match kw {
+                Keyword::Application(Application::Inline, _) => {}
+                Keyword::Application(_, child) => Self::walk_applications(child, out),
+                _ => (),
+            }
+        }
+    }
+
+    /// Number of URIs indexed by this builder, including anchors and aliases.
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn verify_references(&self) -> Result<(), Error> {
         for (referrer, referrent) in self.references() {
             if !self.0.contains_key(referrent) {
@@ -139,6 +177,15 @@ where
             Some(scm) => Ok(scm),
         }
     }
+
+    /// Number of URIs indexed by this Index, including anchors and aliases.
+    pub fn count(&self) -> usize {
+        self.fast.len() + self.slow.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fast.is_empty() && self.slow.is_empty()
+    }
 }
 
 // We implement, rather than derive, Clone in order to avoid requiring
@@ -192,12 +239,24 @@ mod test {
         let schema = build_schema::<CoreAnnotation>(curi.clone(), &schema).unwrap();
 
         let mut builder = IndexBuilder::new();
+        assert!(builder.is_empty());
+        assert_eq!(builder.count(), 0);
+
         builder.add(&schema).unwrap();
+        assert_eq!(builder.count(), 10);
         builder.add_alias(&schema, &alias).unwrap();
+        assert_eq!(builder.count(), 11);
         builder.add_alias(&schema, &schema.curi).unwrap(); // No-op.
+        assert_eq!(builder.count(), 11);
         builder.verify_references().unwrap();
         let index = builder.into_index();
 
+        assert!(!index.is_empty());
+        assert_eq!(index.count(), 11);
+        assert!(IndexBuilder::<CoreAnnotation>::new()
+            .into_index()
+            .is_empty());
+
         assert_eq!(
             index
                 .fast
@@ -243,4 +302,40 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_remove() {
+        let curi = url::Url::parse("http://example/schema").unwrap();
+        let alias = url::Url::parse("http://alias/schema").unwrap();
+        let schema = build_schema::<CoreAnnotation>(
+            curi.clone(),
+            &json!({
+                "$defs": {"one": {"$anchor": "One", "const": 1}},
+                "properties": {"two": {"const": 2}},
+            }),
+        )
+        .unwrap();
+
+        let other_uri = url::Url::parse("http://other/schema").unwrap();
+        let other =
+            build_schema::<CoreAnnotation>(other_uri.clone(), &json!({"const": 3})).unwrap();
+
+        let mut builder = IndexBuilder::new();
+        builder.add(&schema).unwrap();
+        builder.add_alias(&schema, &alias).unwrap();
+        builder.add(&other).unwrap();
+        assert_eq!(builder.count(), 6);
+
+        // Removing by an alias removes the schema, its sub-schemas, anchors, and aliases.
+        assert!(builder.remove(&alias));
+        assert!(!builder.remove(&curi));
+        assert_eq!(builder.count(), 1);
+
+        let index = builder.into_index();
+        assert!(index.fetch(&curi).is_none());
+        assert!(index
+            .fetch(&url::Url::parse("http://example/schema#One").unwrap())
+            .is_none());
+        assert!(index.fetch(&other_uri).is_some());
+    }
 }
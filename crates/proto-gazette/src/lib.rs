@@ -36,3 +36,286 @@ pub mod message_flags {
     /// which are now considered to have committed.
     pub const ACK_TXN: u64 = 0x2;
 }
+
+impl broker::Route {
+    /// Endpoints of Route members, ordered so that members in the `preferred_zone`
+    /// come first, followed by members of other zones. Otherwise, members are in
+    /// Route order. Yields nothing if the Route has no endpoints.
+    pub fn zone_endpoints<'a>(&'a self, preferred_zone: &str) -> impl Iterator<Item = &'a str> {
+        let (preferred, other): (Vec<_>, Vec<_>) = self
+            .members
+            .iter()
+            .zip(self.endpoints.iter())
+            .partition(|(id, _)| id.zone == preferred_zone);
+
+        preferred
+            .into_iter()
+            .chain(other)
+            .map(|(_, endpoint)| endpoint.as_str())
+    }
+
+    /// Zone of the Route's primary member, or None if there is no current primary.
+    pub fn primary_zone(&self) -> Option<&str> {
+        usize::try_from(self.primary)
+            .ok()
+            .and_then(|index| self.members.get(index))
+            .map(|id| id.zone.as_str())
+    }
+}
+
+/// JournalSpecError is a violated invariant of a JournalSpec.
+/// C.f. Gazette's `JournalSpec.Validate`, which enforces these same invariants.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum JournalSpecError {
+    /// Replication is less than one.
+    #[error("invalid replication ({0}; expected >= 1)")]
+    ReplicationTooLow(i32),
+    /// MaxAppendRate is negative.
+    #[error("invalid maxAppendRate ({0}; expected >= 0)")]
+    NegativeMaxAppendRate(i64),
+    /// Fragment length is outside of the range allowed by Gazette.
+    #[error(
+        "invalid fragment length ({0}; expected {min} <= length <= {max})",
+        min = broker::JournalSpec::MIN_FRAGMENT_LENGTH,
+        max = broker::JournalSpec::MAX_FRAGMENT_LENGTH,
+    )]
+    InvalidFragmentLength(i64),
+    /// Fragment has a flush interval, but no stores to flush to.
+    #[error("fragment flushInterval is set, but there are no fragment stores")]
+    EmptyFragmentStoreWithFlushInterval,
+}
+
+impl broker::JournalSpec {
+    /// Minimum allowed length of a journal fragment.
+    pub const MIN_FRAGMENT_LENGTH: i64 = 1 << 10;
+    /// Maximum allowed length of a journal fragment.
+    pub const MAX_FRAGMENT_LENGTH: i64 = 1 << 34;
+
+    /// Validate invariants of the JournalSpec, returning all violations.
+    pub fn validate(&self) -> Result<(), Vec<JournalSpecError>> {
+        let mut errors = Vec::new();
+
+        if self.replication < 1 {
+            errors.push(JournalSpecError::ReplicationTooLow(self.replication));
+        }
+        if self.max_append_rate < 0 {
+            errors.push(JournalSpecError::NegativeMaxAppendRate(
+                self.max_append_rate,
+            ));
+        }
+        if let Some(fragment) = &self.fragment {
+            if !(Self::MIN_FRAGMENT_LENGTH..=Self::MAX_FRAGMENT_LENGTH).contains(&fragment.length) {
+                errors.push(JournalSpecError::InvalidFragmentLength(fragment.length));
+            }
+            if fragment.stores.is_empty() && fragment.flush_interval.is_some() {
+                errors.push(JournalSpecError::EmptyFragmentStoreWithFlushInterval);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Map the `flags` bitmask of the JournalSpec to its most restrictive Flag,
+    /// where O_RDONLY > O_WRONLY > O_RDWR > NOT_SPECIFIED.
+    /// Bits which don't correspond to a Flag are ignored.
+    pub fn effective_flags(&self) -> broker::journal_spec::Flag {
+        use broker::journal_spec::Flag;
+
+        [Flag::ORdonly, Flag::OWronly, Flag::ORdwr]
+            .into_iter()
+            .find(|flag| self.flags & (*flag as u32) != 0)
+            .unwrap_or(Flag::NotSpecified)
+    }
+}
+
+impl broker::BrokerSpec {
+    /// Validate invariants of the BrokerSpec.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.journal_limit == 0 {
+            return Err("journal_limit must be positive");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::broker::{process_spec::Id, Route};
+
+    #[test]
+    fn test_route_zones() {
+        let id = |zone: &str, suffix: &str| Id {
+            zone: zone.to_string(),
+            suffix: suffix.to_string(),
+        };
+        let route = Route {
+            members: vec![
+                id("us-central1", "broker-a"),
+                id("us-east-1", "broker-b"),
+                id("us-east-1", "broker-c"),
+                id("us-west-2", "broker-d"),
+            ],
+            primary: 2,
+            endpoints: vec![
+                "http://a".to_string(),
+                "http://b".to_string(),
+                "http://c".to_string(),
+                "http://d".to_string(),
+            ],
+        };
+
+        assert_eq!(
+            route.zone_endpoints("us-east-1").collect::<Vec<_>>(),
+            vec!["http://b", "http://c", "http://a", "http://d"]
+        );
+        assert_eq!(
+            route.zone_endpoints("us-west-2").collect::<Vec<_>>(),
+            vec!["http://d", "http://a", "http://b", "http://c"]
+        );
+        // An unknown zone yields endpoints in Route order.
+        assert_eq!(
+            route.zone_endpoints("eu-west-1").collect::<Vec<_>>(),
+            vec!["http://a", "http://b", "http://c", "http://d"]
+        );
+        assert_eq!(route.primary_zone(), Some("us-east-1"));
+
+        // Routes without a primary, or without members or endpoints.
+        let no_primary = Route {
+            primary: -1,
+            ..route.clone()
+        };
+        assert_eq!(no_primary.primary_zone(), None);
+
+        let empty = Route::default();
+        assert_eq!(empty.zone_endpoints("us-east-1").count(), 0);
+        assert_eq!(empty.primary_zone(), None);
+
+        let no_endpoints = Route {
+            endpoints: Vec::new(),
+            ..route
+        };
+        assert_eq!(no_endpoints.zone_endpoints("us-east-1").count(), 0);
+        assert_eq!(no_endpoints.primary_zone(), Some("us-east-1"));
+    }
+
+    #[test]
+    fn test_journal_spec_validate() {
+        use super::{broker::journal_spec::Fragment, broker::JournalSpec, JournalSpecError};
+
+        let valid = JournalSpec {
+            name: "acme/anvils/pivot=00".to_string(),
+            replication: 3,
+            max_append_rate: 4 << 20,
+            fragment: Some(Fragment {
+                length: 1 << 29,
+                stores: vec!["s3://bucket/".to_string()],
+                flush_interval: Some(pbjson_types::Duration {
+                    seconds: 3600,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(valid.validate(), Ok(()));
+
+        let mut spec = valid.clone();
+        spec.replication = 0;
+        assert_eq!(
+            spec.validate(),
+            Err(vec![JournalSpecError::ReplicationTooLow(0)])
+        );
+
+        let mut spec = valid.clone();
+        spec.max_append_rate = -1;
+        assert_eq!(
+            spec.validate(),
+            Err(vec![JournalSpecError::NegativeMaxAppendRate(-1)])
+        );
+
+        for length in [0, 1023, (1 << 34) + 1] {
+            let mut spec = valid.clone();
+            spec.fragment.as_mut().unwrap().length = length;
+            assert_eq!(
+                spec.validate(),
+                Err(vec![JournalSpecError::InvalidFragmentLength(length)])
+            );
+        }
+
+        let mut spec = valid.clone();
+        spec.fragment.as_mut().unwrap().stores.clear();
+        assert_eq!(
+            spec.validate(),
+            Err(vec![JournalSpecError::EmptyFragmentStoreWithFlushInterval])
+        );
+        // Without a flush interval, fragments needn't have stores.
+        spec.fragment.as_mut().unwrap().flush_interval = None;
+        assert_eq!(spec.validate(), Ok(()));
+
+        // All violations are returned.
+        let spec = JournalSpec {
+            replication: -1,
+            max_append_rate: -10,
+            ..valid
+        };
+        assert_eq!(
+            spec.validate().unwrap_err(),
+            vec![
+                JournalSpecError::ReplicationTooLow(-1),
+                JournalSpecError::NegativeMaxAppendRate(-10),
+            ]
+        );
+        assert_eq!(
+            JournalSpecError::InvalidFragmentLength(10).to_string(),
+            "invalid fragment length (10; expected 1024 <= length <= 17179869184)"
+        );
+    }
+
+    #[test]
+    fn test_journal_spec_effective_flags() {
+        use super::broker::{journal_spec::Flag, JournalSpec};
+
+        let effective = |flags: u32| {
+            JournalSpec {
+                flags,
+                ..Default::default()
+            }
+            .effective_flags()
+        };
+
+        assert_eq!(effective(0), Flag::NotSpecified);
+        assert_eq!(effective(Flag::ORdonly as u32), Flag::ORdonly);
+        assert_eq!(effective(Flag::OWronly as u32), Flag::OWronly);
+        assert_eq!(effective(Flag::ORdwr as u32), Flag::ORdwr);
+
+        // Combinations map to their most restrictive Flag.
+        assert_eq!(effective(0b111), Flag::ORdonly);
+        assert_eq!(effective(0b110), Flag::OWronly);
+        assert_eq!(effective(0b101), Flag::ORdonly);
+
+        // Unknown bits are ignored.
+        assert_eq!(effective(0b1000), Flag::NotSpecified);
+        assert_eq!(effective(!0b011), Flag::ORdwr);
+    }
+
+    #[test]
+    fn test_broker_spec_validate() {
+        use super::broker::BrokerSpec;
+
+        let spec = BrokerSpec {
+            journal_limit: 1024,
+            ..Default::default()
+        };
+        assert_eq!(spec.validate(), Ok(()));
+
+        let spec = BrokerSpec {
+            journal_limit: 0,
+            ..spec
+        };
+        assert_eq!(spec.validate(), Err("journal_limit must be positive"));
+    }
+}
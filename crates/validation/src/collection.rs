@@ -66,6 +66,16 @@ fn walk_collection(
         errors,
     );
 
+    if name.len() > MAX_COLLECTION_NAME_LEN {
+        Error::NameTooLong {
+            entity: "collection",
+            name: name.to_string(),
+            len: name.len(),
+            max: MAX_COLLECTION_NAME_LEN,
+        }
+        .push(scope, errors);
+    }
+
     if key.is_empty() {
         Error::CollectionKeyEmpty {
             collection: name.to_string(),
@@ -449,6 +459,8 @@ pub fn walk_selector(
     }
 }
 
+/// The maximum length of a collection name, in bytes.
+const MAX_COLLECTION_NAME_LEN: usize = 512;
 /// The default field name for the root document projection.
 const FLOW_DOCUMENT: &str = "flow_document";
 /// The default field name for the document publication time.
@@ -1,3 +1,4 @@
+use doc::shape::type_set_display;
 use json::schema::types;
 use proto_flow::flow::collection_spec::derivation::ShuffleType;
 use url::Url;
@@ -13,6 +14,13 @@ pub enum Error {
         name: String,
         unmatched: String,
     },
+    #[error("{entity} name {name} is {len} bytes long, which exceeds the maximum of {max}")]
+    NameTooLong {
+        entity: &'static str,
+        name: String,
+        len: usize,
+        max: usize,
+    },
     #[error(
         "{lhs_entity} {lhs_name} {error_class} {rhs_entity} {rhs_name}, defined at {rhs_scope}"
     )]
@@ -78,7 +86,7 @@ pub enum Error {
     PtrRegexUnmatched { ptr: String, unmatched: String },
     #[error("location {ptr} is prohibited from ever existing by the schema {schema}")]
     PtrCannotExist { ptr: String, schema: Url },
-    #[error("location {ptr} accepts {type_:?} in schema {schema}, but locations used as keys may only be null-able integers, strings, or booleans")]
+    #[error("location {ptr} accepts {} in schema {schema}, but locations used as keys may only be null-able integers, strings, or booleans", type_set_display(*.type_))]
     KeyWrongType {
         ptr: String,
         type_: types::Set,
@@ -110,7 +118,7 @@ pub enum Error {
         canonical_ptr: String,
         wrong_ptr: String,
     },
-    #[error("{category} partition selector field {field} value {value} is incompatible with the projections type, {type_:?}")]
+    #[error("{category} partition selector field {field} value {value} is incompatible with the projections type, {}", type_set_display(*.type_))]
     SelectorTypeMismatch {
         category: String,
         field: String,
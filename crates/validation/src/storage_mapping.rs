@@ -156,8 +156,20 @@ pub fn walk_all_storage_mappings(
         );
     }
 
+    // A mapping may be repeated by multiple catalog sources, so long as each
+    // repetition has the same stores. Other duplicates or overlapping prefixes
+    // are errors, as it would be ambiguous which stores should be used.
+    let distinct = storage_mappings
+        .iter()
+        .enumerate()
+        .filter(|(index, m)| match index.checked_sub(1) {
+            Some(prior) => !is_repeated_mapping(&storage_mappings[prior], m),
+            None => true,
+        })
+        .map(|(_, m)| m);
+
     indexed::walk_duplicates(
-        storage_mappings.iter().map(|m| {
+        distinct.map(|m| {
             (
                 "storageMapping",
                 // Prefixes explicitly end in a '/'. Strip it for the sake of
@@ -173,6 +185,12 @@ pub fn walk_all_storage_mappings(
     );
 }
 
+// is_repeated_mapping returns true if |rhs| has the same prefix and stores as |lhs|.
+fn is_repeated_mapping(lhs: &tables::StorageMapping, rhs: &tables::StorageMapping) -> bool {
+    lhs.prefix == rhs.prefix
+        && serde_json::to_value(&lhs.stores).ok() == serde_json::to_value(&rhs.stores).ok()
+}
+
 // mapped_stores maps the |entity| identified by |name| to its corresponding
 // StorageMapping stores. Or, if no StorageMapping is matched, it returns an
 // empty slice and records an error.
@@ -228,7 +246,7 @@ fn lookup_mapping<'a>(
 
 #[cfg(test)]
 mod test {
-    use super::lookup_mapping;
+    use super::{lookup_mapping, walk_all_storage_mappings};
     use models::Prefix;
 
     #[test]
@@ -248,4 +266,39 @@ mod test {
         assert!(lookup_mapping(&mappings, "bar/two/3").is_some());
         assert!(lookup_mapping(&mappings, "bar/uwo/4").is_none());
     }
+
+    #[test]
+    fn test_repeated_mappings() {
+        let scope_a = url::Url::parse("http://scope/a").unwrap();
+        let scope_b = url::Url::parse("http://scope/b").unwrap();
+        let stores = |bucket: &str| -> Vec<models::Store> {
+            serde_json::from_value(serde_json::json!([{"provider": "S3", "bucket": bucket}]))
+                .unwrap()
+        };
+
+        // A mapping repeated with identical stores is not an error.
+        let mut mappings = tables::StorageMappings::new();
+        mappings.insert_row(&scope_a, Prefix::new("acme/"), stores("a-bucket"));
+        mappings.insert_row(&scope_b, Prefix::new("acme/"), stores("a-bucket"));
+
+        let mut errors = tables::Errors::new();
+        walk_all_storage_mappings(&mappings, &mut errors);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        // But a repetition with different stores, or an overlapping prefix, is.
+        mappings.insert_row(&scope_b, Prefix::new("acme/"), stores("b-bucket"));
+        mappings.insert_row(&scope_b, Prefix::new("acme/events/"), stores("a-bucket"));
+
+        let mut errors = tables::Errors::new();
+        walk_all_storage_mappings(&mappings, &mut errors);
+
+        let errors: Vec<String> = errors.iter().map(|e| e.error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "storageMapping acme collides with storageMapping acme, defined at http://scope/b",
+                "storageMapping acme is a prohibited prefix of storageMapping acme/events, defined at http://scope/b",
+            ]
+        );
+    }
 }
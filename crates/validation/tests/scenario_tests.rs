@@ -440,6 +440,27 @@ test://example/catalog.yaml:
     insta::assert_debug_snapshot!(errors);
 }
 
+#[test]
+fn test_collection_name_length() {
+    // Names of exactly the maximum length are allowed, and longer names are not.
+    let errors = run_test_errors(
+        &GOLDEN,
+        &format!(
+            r#"
+test://example/catalog.yaml:
+  collections:
+    testing/{ok}: &spec
+      schema: test://example/int-string.schema
+      key: [/int]
+    testing/{too_long}: *spec
+"#,
+            ok = "a".repeat(512 - "testing/".len()),
+            too_long = "b".repeat(513 - "testing/".len()),
+        ),
+    );
+    insta::assert_debug_snapshot!(errors);
+}
+
 #[test]
 fn test_invalid_partition_names_and_duplicates() {
     let errors = run_test_errors(